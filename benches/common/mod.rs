@@ -0,0 +1,19 @@
+/**
+ * Helpers shared by the sorting benchmarks.
+ */
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/**
+ * The average time of `runs` runs of `sort`. Every run sorts a fresh copy, the copy is timed as well
+ * but is the same for every sort.
+ */
+pub fn time_sort(input: &[u64], runs: u32, sort: impl Fn(&mut [u64])) -> Duration {
+    let start = Instant::now();
+    for _ in 0..runs {
+        let mut arr = input.to_vec();
+        sort(&mut arr);
+        black_box(arr);
+    }
+    start.elapsed() / runs
+}
@@ -0,0 +1,99 @@
+/**
+ * CSR Graph Benchmark
+ *
+ * Compares BFS, DFS and heap-based Dijkstra on the nested `HashMap` graphs against the same
+ * searches on a `CsrGraph` built from them.
+ *
+ * Run with `cargo bench --bench csr_graph`.
+ */
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use data_structures_algorithms_rust::graphs::csr_graph::CsrGraph;
+use data_structures_algorithms_rust::graphs::generators::Rng;
+use data_structures_algorithms_rust::graphs::{bfs_graph, dfs_graph, dijkstras_shortest_path_heap};
+
+const NODES: u32 = 50_000;
+const EDGES_PER_NODE: u32 = 10;
+const RUNS: u32 = 5;
+
+// The HashMap graphs are keyed by `char`, so map node numbers past '\0' into valid chars.
+fn node(n: u32) -> char {
+    char::from_u32(n + 0x10000).unwrap()
+}
+
+fn time<R>(mut f: impl FnMut() -> R) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(f());
+    }
+    start.elapsed() / RUNS
+}
+
+fn report(name: &str, hashmap: Duration, csr: Duration) {
+    println!(
+        "{:<10} hashmap: {:>10.2?}  csr: {:>10.2?}  speedup: {:>5.1}x",
+        name,
+        hashmap,
+        csr,
+        hashmap.as_secs_f64() / csr.as_secs_f64()
+    );
+}
+
+fn main() {
+    let mut rng = Rng::new(1);
+    let mut unweighted: HashMap<char, Vec<char>> = HashMap::new();
+    let mut weighted: HashMap<char, HashMap<char, u32>> = HashMap::new();
+    for from in 0..NODES {
+        let neighbors = unweighted.entry(node(from)).or_default();
+        let weights = weighted.entry(node(from)).or_default();
+        // A chain keeps the graph connected, the remaining edges are random.
+        if from + 1 < NODES {
+            neighbors.push(node(from + 1));
+            weights.insert(node(from + 1), 1 + rng.below(100) as u32);
+        }
+        for _ in 1..EDGES_PER_NODE {
+            let to = node(rng.below(NODES as u64) as u32);
+            neighbors.push(to);
+            weights.insert(to, 1 + rng.below(100) as u32);
+        }
+    }
+    // A needle that is never found forces a full traversal.
+    let (start, needle) = (node(0), node(NODES));
+
+    let csr_unweighted = CsrGraph::from_adjacency(&unweighted);
+    let csr_weighted = CsrGraph::from_weighted(&weighted);
+    let (csr_start, csr_needle) = (csr_unweighted.index_of(&start).unwrap(), NODES);
+
+    println!("{} nodes, {} edges, average of {} runs", NODES, csr_unweighted.edge_count(), RUNS);
+
+    report(
+        "bfs",
+        time(|| bfs_graph::breadth_first_search(&unweighted, start, needle)),
+        time(|| csr_unweighted.breadth_first_search(csr_start, csr_needle)),
+    );
+
+    // The recursive HashMap DFS can go as deep as the graph, so give it a large stack.
+    let dfs_hashmap = std::thread::Builder::new()
+        .stack_size(1 << 30)
+        .spawn({
+            let unweighted = unweighted.clone();
+            move || time(|| dfs_graph::depth_first_search(&unweighted, start, needle))
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    report(
+        "dfs",
+        dfs_hashmap,
+        time(|| csr_unweighted.depth_first_search(csr_start, csr_needle)),
+    );
+
+    let csr_start = csr_weighted.index_of(&start).unwrap();
+    report(
+        "dijkstra",
+        time(|| dijkstras_shortest_path_heap::dijkstras_shortest_path(&weighted, start, needle)),
+        time(|| csr_weighted.dijkstra_distances(csr_start)),
+    );
+}
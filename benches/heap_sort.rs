@@ -0,0 +1,43 @@
+/**
+ * Heap Sort Benchmark
+ *
+ * Compares the in-place binary, bottom-up and d-ary heap sorts against `slice::sort_unstable`
+ * on random, sorted and reversed input.
+ *
+ * Run with `cargo bench --bench heap_sort`.
+ */
+mod common;
+
+use data_structures_algorithms_rust::graphs::generators::Rng;
+use data_structures_algorithms_rust::sorting::heap_sort::{bottom_up_heap_sort, d_ary_heap_sort, heap_sort};
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+const RUNS: u32 = 5;
+
+fn main() {
+    let mut rng = Rng::new(1);
+    println!("average of {} runs", RUNS);
+    println!(
+        "{:<8} {:>9} {:>12} {:>12} {:>12} {:>12}",
+        "input", "n", "binary", "bottom-up", "4-ary", "unstable"
+    );
+
+    for n in SIZES {
+        let random: Vec<u64> = (0..n).map(|_| rng.next_u64()).collect();
+        let mut sorted = random.clone();
+        sorted.sort_unstable();
+        let reversed: Vec<u64> = sorted.iter().rev().copied().collect();
+
+        for (name, input) in [("random", &random), ("sorted", &sorted), ("reversed", &reversed)] {
+            println!(
+                "{:<8} {:>9} {:>12.2?} {:>12.2?} {:>12.2?} {:>12.2?}",
+                name,
+                n,
+                common::time_sort(input, RUNS, heap_sort),
+                common::time_sort(input, RUNS, bottom_up_heap_sort),
+                common::time_sort(input, RUNS, |arr| d_ary_heap_sort(arr, 4)),
+                common::time_sort(input, RUNS, |arr| arr.sort_unstable()),
+            );
+        }
+    }
+}
@@ -0,0 +1,48 @@
+/**
+ * Parallel Sort Benchmark
+ *
+ * Times `parallel_merge_sort` and `parallel_quick_sort` on random input with 1 to 8 threads, next to
+ * the sequential `merge_sort` and `quick_sort`, and prints the speedup over the sequential version.
+ *
+ * Run with `cargo bench --bench parallel_sort`.
+ */
+mod common;
+
+use data_structures_algorithms_rust::graphs::generators::Rng;
+use data_structures_algorithms_rust::sorting::merge_sort::merge_sort;
+use data_structures_algorithms_rust::sorting::parallel_sort::{parallel_merge_sort, parallel_quick_sort};
+use data_structures_algorithms_rust::sorting::quick_sort::quick_sort;
+
+const SIZES: [usize; 2] = [1_000_000, 10_000_000];
+const THREADS: [usize; 4] = [1, 2, 4, 8];
+const RUNS: u32 = 3;
+
+// One row for the sequential sort and one per thread count for the parallel one.
+fn report(name: &str, input: &[u64], sequential: fn(&mut [u64]), parallel: fn(&mut [u64], usize)) {
+    let baseline = common::time_sort(input, RUNS, sequential);
+    println!("{:<6} {:>9} {:>8} {:>12.2?} {:>8.2}", name, input.len(), "-", baseline, 1.0);
+    for threads in THREADS {
+        let elapsed = common::time_sort(input, RUNS, |arr| parallel(arr, threads));
+        println!(
+            "{:<6} {:>9} {:>8} {:>12.2?} {:>8.2}",
+            name,
+            input.len(),
+            threads,
+            elapsed,
+            baseline.as_secs_f64() / elapsed.as_secs_f64()
+        );
+    }
+}
+
+fn main() {
+    let mut rng = Rng::new(1);
+    let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get());
+    println!("average of {} runs, {} cores available", RUNS, cores);
+    println!("{:<6} {:>9} {:>8} {:>12} {:>8}", "sort", "n", "threads", "time", "speedup");
+
+    for n in SIZES {
+        let random: Vec<u64> = (0..n).map(|_| rng.next_u64()).collect();
+        report("merge", &random, merge_sort, parallel_merge_sort);
+        report("quick", &random, quick_sort, parallel_quick_sort);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod linked_lists;
 pub mod arrays;
+pub mod union_find;
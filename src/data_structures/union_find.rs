@@ -0,0 +1,149 @@
+/**
+ * Union-Find (Disjoint Set)
+ *
+ * Keeps track of elements `0..n` partitioned into disjoint sets, supporting merging two sets and
+ * asking which set an element belongs to.
+ *
+ * Each set is a tree whose root is the representative of the set. Two optimizations keep the trees
+ * flat:
+ * - Union by rank: the shorter tree is always attached below the root of the taller one.
+ * - Path compression: `find` points every node it walks past directly at the root.
+ *
+ * Together they give an amortized cost of O(α(n)) per operation, where α is the inverse Ackermann
+ * function, which is at most 4 for any practical n.
+ *
+ * Time Complexity: O(α(n)) amortized for find and union
+ * Space Complexity: O(n)
+ */
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /**
+     * Create `n` singleton sets `{0}, {1}, ..., {n - 1}`.
+     */
+    pub fn new(n: usize) -> Self {
+        UnionFind { parent: (0..n).collect(), rank: vec![0; n], size: vec![1; n], sets: n }
+    }
+
+    /**
+     * Add a new singleton set and return its element.
+     */
+    pub fn add(&mut self) -> usize {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.size.push(1);
+        self.sets += 1;
+        element
+    }
+
+    /**
+     * Number of elements.
+     */
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /**
+     * Number of disjoint sets.
+     */
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /**
+     * Find the representative of the set containing `element`.
+     */
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Path compression: point everything on the way directly at the root
+        let mut current = element;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /**
+     * Merge the sets containing `a` and `b`.
+     *
+     * Returns false if they were already in the same set.
+     */
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // Union by rank: hang the shorter tree below the taller one
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    /**
+     * Check whether `a` and `b` are in the same set.
+     */
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /**
+     * Number of elements in the set containing `element`.
+     */
+    pub fn set_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.set_count(), 6);
+        assert!(!sets.connected(0, 1));
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.set_size(2), 4);
+        assert_eq!(sets.set_count(), 3);
+
+        let new = sets.add();
+        assert_eq!(new, 6);
+        assert_eq!(sets.len(), 7);
+        assert!(sets.union(new, 5));
+        assert_eq!(sets.set_count(), 3);
+    }
+}
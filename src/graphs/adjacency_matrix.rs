@@ -0,0 +1,393 @@
+/**
+ * Adjacency Matrix Graph
+ *
+ * A graph stored as a flat `Vec` of `V * V` optional edge weights, where the weight of the edge
+ * from node `u` to node `v` lives at `u * V + v`. Nodes are given dense `usize` indices and the
+ * external IDs can be mapped back with `id` and `to_ids`.
+ *
+ * Checking or updating a single edge is O(1), but every node costs a full row of storage, so this
+ * representation is meant for dense graphs. On dense graphs the O(V^2) algorithms below are optimal,
+ * because they have to look at every one of the O(V^2) edges anyway.
+ *
+ * Space Complexity: O(V^2)
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use super::bit_matrix::BitMatrix;
+
+pub struct AdjacencyMatrix<N> {
+    size: usize,
+    weights: Vec<Option<u32>>,
+    ids: Vec<N>,
+    index: HashMap<N, usize>,
+}
+
+impl<N: Hash + Eq + Clone> AdjacencyMatrix<N> {
+    /**
+     * Create a matrix for the given nodes without any edges.
+     */
+    pub fn with_nodes<I: IntoIterator<Item = N>>(nodes: I) -> Self {
+        let mut ids = Vec::new();
+        let mut index = HashMap::new();
+        for id in nodes {
+            if !index.contains_key(&id) {
+                index.insert(id.clone(), ids.len());
+                ids.push(id);
+            }
+        }
+        let size = ids.len();
+        AdjacencyMatrix { size, weights: vec![None; size * size], ids, index }
+    }
+
+    /**
+     * Get the dense index of an external ID.
+     */
+    pub fn index_of(&self, id: &N) -> Option<usize> {
+        self.index.get(id).copied()
+    }
+
+    /**
+     * Get the external ID of a dense index.
+     */
+    pub fn id(&self, node: usize) -> Option<&N> {
+        self.ids.get(node)
+    }
+
+    /**
+     * Map a path of dense indices back to external IDs.
+     */
+    pub fn to_ids(&self, path: &[usize]) -> Vec<N> {
+        path.iter().map(|&node| self.ids[node].clone()).collect()
+    }
+}
+
+impl<N: Hash + Eq + Clone> From<&HashMap<N, Vec<N>>> for AdjacencyMatrix<N> {
+    /**
+     * Convert the unweighted adjacency lists used by `bfs_graph` and `dfs_graph`. Every edge gets a weight of 1.
+     */
+    fn from(graph: &HashMap<N, Vec<N>>) -> Self {
+        let nodes = graph.iter().flat_map(|(from, neighbors)| std::iter::once(from).chain(neighbors));
+        let mut matrix = AdjacencyMatrix::with_nodes(nodes.cloned());
+        for (from, neighbors) in graph {
+            let from = matrix.index[from];
+            for to in neighbors {
+                let to = matrix.index[to];
+                matrix.set_edge(from, to, 1);
+            }
+        }
+        matrix
+    }
+}
+
+impl<N: Hash + Eq + Clone> From<&HashMap<N, HashMap<N, u32>>> for AdjacencyMatrix<N> {
+    /**
+     * Convert the weighted adjacency maps used by the Dijkstra modules.
+     */
+    fn from(graph: &HashMap<N, HashMap<N, u32>>) -> Self {
+        let nodes = graph.iter().flat_map(|(from, neighbors)| std::iter::once(from).chain(neighbors.keys()));
+        let mut matrix = AdjacencyMatrix::with_nodes(nodes.cloned());
+        for (from, neighbors) in graph {
+            let from = matrix.index[from];
+            for (to, &weight) in neighbors {
+                let to = matrix.index[to];
+                matrix.set_edge(from, to, weight);
+            }
+        }
+        matrix
+    }
+}
+
+impl<N> AdjacencyMatrix<N> {
+    pub fn node_count(&self) -> usize {
+        self.size
+    }
+
+    /**
+     * The weight of the edge from `from` to `to`, if there is one.
+     */
+    pub fn weight(&self, from: usize, to: usize) -> Option<u32> {
+        self.weights[from * self.size + to]
+    }
+
+    /**
+     * Add an edge or replace the weight of an existing one.
+     */
+    pub fn set_edge(&mut self, from: usize, to: usize, weight: u32) {
+        self.weights[from * self.size + to] = Some(weight);
+    }
+
+    pub fn remove_edge(&mut self, from: usize, to: usize) {
+        self.weights[from * self.size + to] = None;
+    }
+
+    /**
+     * Dijkstra's Shortest Path Algorithm with an array scan instead of a heap.
+     *
+     * Returns the lowest weight path from start to needle, if it exists.
+     *
+     * Greedy Algorithm
+     *
+     * Time Complexity: O(|V|^2)
+     * Space Complexity: O(|V|)
+     */
+    pub fn dijkstras_shortest_path(&self, start: usize, needle: usize) -> Option<Vec<usize>> {
+        let (distances, predecessors) = self.dijkstra(start);
+        distances[needle]?;
+
+        let mut path = vec![needle];
+        let mut prev = predecessors[needle];
+        while let Some(node) = prev {
+            path.push(node);
+            prev = predecessors[node];
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /**
+     * Lowest total weight from source to every node, `None` if unreachable.
+     *
+     * Time Complexity: O(|V|^2)
+     * Space Complexity: O(|V|)
+     */
+    pub fn dijkstra_distances(&self, source: usize) -> Vec<Option<u64>> {
+        self.dijkstra(source).0
+    }
+
+    fn dijkstra(&self, source: usize) -> (Vec<Option<u64>>, Vec<Option<usize>>) {
+        let mut distances = vec![None; self.size];
+        let mut predecessors = vec![None; self.size];
+        let mut visited = vec![false; self.size];
+        distances[source] = Some(0);
+
+        loop {
+            // Scan the distance array for the closest unvisited node
+            let mut closest: Option<(usize, u64)> = None;
+            for node in 0..self.size {
+                if let (false, Some(distance)) = (visited[node], distances[node]) {
+                    if closest.is_none_or(|(_, best)| distance < best) {
+                        closest = Some((node, distance));
+                    }
+                }
+            }
+            let Some((current, distance)) = closest else {
+                break;
+            };
+            visited[current] = true;
+
+            for neighbor in 0..self.size {
+                if let (false, Some(weight)) = (visited[neighbor], self.weight(current, neighbor)) {
+                    let next = distance + weight as u64;
+                    if distances[neighbor].is_none_or(|known| next < known) {
+                        distances[neighbor] = Some(next);
+                        predecessors[neighbor] = Some(current);
+                    }
+                }
+            }
+        }
+
+        (distances, predecessors)
+    }
+
+    /**
+     * Floyd-Warshall All Pairs Shortest Paths
+     *
+     * Returns a flat `V * V` matrix where `result[i * V + j]` is the lowest total weight from `i` to `j`,
+     * `None` if `j` cannot be reached from `i`.
+     *
+     * Dynamic Programming
+     *
+     * Time Complexity: O(|V|^3)
+     * Space Complexity: O(|V|^2)
+     */
+    pub fn floyd_warshall(&self) -> Vec<Option<u64>> {
+        let n = self.size;
+        let mut distances: Vec<Option<u64>> = self.weights.iter().map(|w| w.map(u64::from)).collect();
+        for node in 0..n {
+            distances[node * n + node] = Some(0);
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                let Some(through_k) = distances[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    if let Some(rest) = distances[k * n + j] {
+                        let candidate = through_k + rest;
+                        if distances[i * n + j].is_none_or(|known| candidate < known) {
+                            distances[i * n + j] = Some(candidate);
+                        }
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+
+    /**
+     * Prim's Minimum Spanning Tree Algorithm
+     *
+     * Treats the graph as undirected: nodes `u` and `v` are connected if there is an edge in either
+     * direction, using the lighter one if there are two. If the graph is disconnected the result is a
+     * minimum spanning forest.
+     *
+     * Returns the tree edges as `(parent, child, weight)`.
+     *
+     * Greedy Algorithm
+     *
+     * Time Complexity: O(|V|^2)
+     * Space Complexity: O(|V|)
+     */
+    pub fn prim(&self) -> Vec<(usize, usize, u32)> {
+        let mut in_tree = vec![false; self.size];
+        // The lightest known edge connecting each node to the tree
+        let mut best: Vec<Option<(u32, usize)>> = vec![None; self.size];
+        let mut edges = Vec::new();
+
+        for root in 0..self.size {
+            if in_tree[root] {
+                continue;
+            }
+            let mut current = root;
+            loop {
+                in_tree[current] = true;
+                for node in 0..self.size {
+                    if in_tree[node] {
+                        continue;
+                    }
+                    let weight = match (self.weight(current, node), self.weight(node, current)) {
+                        (Some(a), Some(b)) => Some(a.min(b)),
+                        (a, b) => a.or(b),
+                    };
+                    if let Some(weight) = weight {
+                        if best[node].is_none_or(|(known, _)| weight < known) {
+                            best[node] = Some((weight, current));
+                        }
+                    }
+                }
+
+                let next = (0..self.size)
+                    .filter(|&node| !in_tree[node])
+                    .filter_map(|node| best[node].map(|(weight, parent)| (weight, node, parent)))
+                    .min();
+                match next {
+                    Some((weight, node, parent)) => {
+                        edges.push((parent, node, weight));
+                        current = node;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        edges
+    }
+
+    /**
+     * Transitive Closure (Warshall's Algorithm)
+     *
+     * Returns a `BitMatrix` where bit `(i, j)` is set if there is a path of at least one edge from `i`
+     * to `j`. Each row is a bitset, so "everything `k` reaches is also reached by `i`" is one OR of
+     * two rows.
+     *
+     * Time Complexity: O(|V|^3 / 64)
+     * Space Complexity: O(|V|^2 / 64)
+     */
+    pub fn transitive_closure(&self) -> BitMatrix {
+        let mut closure = BitMatrix::new(self.size, self.size);
+        for from in 0..self.size {
+            for to in 0..self.size {
+                if self.weight(from, to).is_some() {
+                    closure.set(from, to);
+                }
+            }
+        }
+
+        for k in 0..self.size {
+            for i in 0..self.size {
+                if closure.get(i, k) {
+                    closure.union_rows(i, k);
+                }
+            }
+        }
+
+        closure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_graph() -> HashMap<char, HashMap<char, u32>> {
+        let mut graph = HashMap::new();
+        graph.insert('A', HashMap::from([('B', 3), ('C', 6), ('D', 1)]));
+        graph.insert('B', HashMap::from([('C', 2), ('E', 7)]));
+        graph.insert('C', HashMap::from([('F', 2)]));
+        graph.insert('D', HashMap::from([('B', 1), ('E', 3)]));
+        graph.insert('E', HashMap::from([('F', 8)]));
+        graph.insert('F', HashMap::new());
+        graph
+    }
+
+    #[test]
+    fn test_conversion() {
+        let mut unweighted = HashMap::new();
+        unweighted.insert('A', vec!['B']);
+        unweighted.insert('B', vec!['C']);
+        let matrix = AdjacencyMatrix::from(&unweighted);
+        let (a, b, c) = (matrix.index_of(&'A').unwrap(), matrix.index_of(&'B').unwrap(), matrix.index_of(&'C').unwrap());
+        assert_eq!(matrix.node_count(), 3);
+        assert_eq!(matrix.weight(a, b), Some(1));
+        assert_eq!(matrix.weight(b, a), None);
+        assert_eq!(matrix.weight(b, c), Some(1));
+    }
+
+    #[test]
+    fn test_dijkstras_shortest_path_and_floyd_warshall() {
+        let matrix = AdjacencyMatrix::from(&create_test_graph());
+        let a = matrix.index_of(&'A').unwrap();
+        let f = matrix.index_of(&'F').unwrap();
+
+        let path = matrix.dijkstras_shortest_path(a, f).unwrap();
+        assert_eq!(matrix.to_ids(&path), vec!['A', 'D', 'B', 'C', 'F']);
+        assert_eq!(matrix.dijkstras_shortest_path(f, a), None);
+
+        let n = matrix.node_count();
+        let all_pairs = matrix.floyd_warshall();
+        for source in 0..n {
+            assert_eq!(&all_pairs[source * n..(source + 1) * n], &matrix.dijkstra_distances(source)[..]);
+        }
+        assert_eq!(all_pairs[a * n + f], Some(6));
+    }
+
+    #[test]
+    fn test_prim() {
+        let matrix = AdjacencyMatrix::from(&create_test_graph());
+        let tree = matrix.prim();
+        assert_eq!(tree.len(), matrix.node_count() - 1);
+        // A-D 1, D-B 1, B-C 2, C-F 2, D-E 3
+        assert_eq!(tree.iter().map(|&(_, _, weight)| weight).sum::<u32>(), 9);
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        let mut graph = HashMap::new();
+        graph.insert('A', vec!['B']);
+        graph.insert('B', vec!['C']);
+        graph.insert('C', vec!['A']);
+        graph.insert('D', vec!['A']);
+        let matrix = AdjacencyMatrix::from(&graph);
+        let closure = matrix.transitive_closure();
+        let (a, d) = (matrix.index_of(&'A').unwrap(), matrix.index_of(&'D').unwrap());
+
+        assert_eq!(closure.count_ones(a), 3);
+        assert!(closure.get(a, a));
+        assert!(!closure.get(a, d));
+        assert!(!closure.get(d, d));
+        assert_eq!(closure.count_ones(d), 3);
+    }
+}
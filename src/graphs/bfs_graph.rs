@@ -13,7 +13,7 @@ use std::collections::{HashMap, VecDeque, HashSet};
 
 type Graph = HashMap<char, Vec<char>>;
 
-fn breadth_first_search(graph: &Graph, start: char, needle: char) -> Option<Vec<char>> {
+pub fn breadth_first_search(graph: &Graph, start: char, needle: char) -> Option<Vec<char>> {
     let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
     let mut predecessors = HashMap::new();
@@ -48,6 +48,37 @@ fn breadth_first_search(graph: &Graph, start: char, needle: char) -> Option<Vec<
     None // Needle not found
 }
 
+/**
+ * Breadth First Search Tree
+ *
+ * Searches the whole graph from start and returns the `predecessors` map instead of a single path.
+ * It maps every reachable node to its parent in the shortest path tree, and start to '\0'.
+ * `trees::lowest_common_ancestor` can answer queries on the tree.
+ */
+pub fn bfs_tree(graph: &Graph, start: char) -> HashMap<char, char> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+    let mut predecessors = HashMap::new();
+
+    queue.push_back(start);
+    visited.insert(start);
+    predecessors.insert(start, '\0'); // Use a dummy char to indicate the start
+
+    while let Some(current) = queue.pop_front() {
+        if let Some(neighbors) = graph.get(&current) {
+            for &neighbor in neighbors {
+                if !visited.contains(&neighbor) {
+                    queue.push_back(neighbor);
+                    visited.insert(neighbor);
+                    predecessors.insert(neighbor, current);
+                }
+            }
+        }
+    }
+
+    predecessors
+}
+
 /**
  * Recursive implementation of Breadth First Search
  */
@@ -132,6 +163,16 @@ mod tests {
         assert_eq!(breadth_first_search(&graph, 'A', 'G'), Some(vec!['A', 'C', 'E', 'G']));
     }
 
+    #[test]
+    fn test_bfs_tree() {
+        let tree = bfs_tree(&create_test_graph(), 'A');
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree[&'A'], '\0');
+        assert_eq!(tree[&'E'], 'C');
+        assert_eq!(tree[&'G'], 'E');
+        assert_eq!(bfs_tree(&create_test_graph(), 'E').len(), 2);
+    }
+
     #[test]
     fn test_breadth_first_search_path_not_found() {
         let graph = create_test_graph();
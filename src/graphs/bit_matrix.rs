@@ -0,0 +1,115 @@
+/**
+ * Bit Matrix
+ *
+ * A dense boolean matrix where every row is a bitset packed into `u64` words.
+ *
+ * Used for reachability (transitive closure) where a whole row can be merged into another with one
+ * bitwise OR per 64 columns instead of one operation per column.
+ *
+ * Space Complexity: O(rows * columns / 64)
+ */
+pub struct BitMatrix {
+    rows: usize,
+    columns: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitMatrix {
+    /**
+     * Create a matrix with every bit cleared.
+     */
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let words_per_row = columns.div_ceil(64);
+        BitMatrix { rows, columns, words_per_row, words: vec![0; rows * words_per_row] }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> bool {
+        assert!(column < self.columns, "column out of bounds");
+        self.row(row)[column / 64] & (1 << (column % 64)) != 0
+    }
+
+    pub fn set(&mut self, row: usize, column: usize) {
+        assert!(column < self.columns, "column out of bounds");
+        self.row_mut(row)[column / 64] |= 1 << (column % 64);
+    }
+
+    /**
+     * OR the bits of row `source` into row `target`.
+     *
+     * Time Complexity: O(columns / 64)
+     */
+    pub fn union_rows(&mut self, target: usize, source: usize) {
+        if target == source {
+            return;
+        }
+        let width = self.words_per_row;
+        let (target_start, source_start) = (target * width, source * width);
+        for i in 0..width {
+            let bits = self.words[source_start + i];
+            self.words[target_start + i] |= bits;
+        }
+    }
+
+    /**
+     * The columns that are set in a row, in increasing order.
+     */
+    pub fn ones(&self, row: usize) -> impl Iterator<Item = usize> + '_ {
+        self.row(row).iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+
+    /**
+     * The number of bits set in a row.
+     */
+    pub fn count_ones(&self, row: usize) -> usize {
+        self.row(row).iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        assert!(row < self.rows, "row out of bounds");
+        &self.words[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    fn row_mut(&mut self, row: usize) -> &mut [u64] {
+        assert!(row < self.rows, "row out of bounds");
+        &mut self.words[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_matrix() {
+        let mut matrix = BitMatrix::new(3, 130);
+        matrix.set(0, 1);
+        matrix.set(0, 129);
+        matrix.set(1, 64);
+        assert!(matrix.get(0, 129));
+        assert!(!matrix.get(0, 64));
+
+        matrix.union_rows(1, 0);
+        assert_eq!(matrix.ones(1).collect::<Vec<_>>(), vec![1, 64, 129]);
+        assert_eq!(matrix.count_ones(1), 3);
+        assert_eq!(matrix.count_ones(2), 0);
+    }
+}
@@ -0,0 +1,418 @@
+/**
+ * Centrality Metrics
+ *
+ * Scores that rank nodes by how important they are to the structure of a graph, for example which
+ * services in a call graph the most traffic depends on. Every function returns a map from node to score.
+ *
+ * - Degree centrality: the fraction of the other nodes a node has edges to.
+ * - Closeness centrality: how short the paths from a node to everything it can reach are.
+ * - Betweenness centrality (Brandes): how many shortest paths between other nodes pass through a node.
+ * - PageRank: the probability that a random surfer following edges ends up at a node.
+ *
+ * The graphs are directed. Undirected graphs list every edge in both directions, as in `bfs_graph`.
+ */
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+type Graph<N> = HashMap<N, Vec<N>>;
+type WeightedGraph<N> = HashMap<N, HashMap<N, u32>>;
+
+// Dense numbering of the nodes so the per-node state can live in vectors.
+struct Indexed<N> {
+    nodes: Vec<N>,
+    adjacency: Vec<Vec<(usize, u32)>>,
+}
+
+impl<N: Hash + Eq + Clone> Indexed<N> {
+    fn new<'a, I, E>(graph: I) -> Self
+    where
+        N: 'a,
+        I: Iterator<Item = (&'a N, E)>,
+        E: Iterator<Item = (&'a N, u32)>,
+    {
+        let mut nodes = Vec::new();
+        let mut index = HashMap::new();
+        let mut intern = |node: &N| -> usize {
+            *index.entry(node.clone()).or_insert_with(|| {
+                nodes.push(node.clone());
+                nodes.len() - 1
+            })
+        };
+
+        let mut edges = Vec::new();
+        for (from, neighbors) in graph {
+            let from = intern(from);
+            for (to, weight) in neighbors {
+                edges.push((from, intern(to), weight));
+            }
+        }
+
+        let mut adjacency = vec![Vec::new(); nodes.len()];
+        for (from, to, weight) in edges {
+            adjacency[from].push((to, weight));
+        }
+        Indexed { nodes, adjacency }
+    }
+
+    fn unweighted(graph: &Graph<N>) -> Self {
+        Self::new(graph.iter().map(|(from, neighbors)| (from, neighbors.iter().map(|to| (to, 1)))))
+    }
+
+    fn weighted(graph: &WeightedGraph<N>) -> Self {
+        Self::new(graph.iter().map(|(from, neighbors)| (from, neighbors.iter().map(|(to, &weight)| (to, weight)))))
+    }
+
+    fn scores(&self, values: Vec<f64>) -> HashMap<N, f64> {
+        self.nodes.iter().cloned().zip(values).collect()
+    }
+}
+
+/**
+ * Degree Centrality
+ *
+ * The number of outgoing edges of each node divided by the `V - 1` other nodes it could connect to.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V)
+ */
+pub fn degree_centrality<N: Hash + Eq + Clone>(graph: &Graph<N>) -> HashMap<N, f64> {
+    let indexed = Indexed::unweighted(graph);
+    let others = indexed.nodes.len().saturating_sub(1).max(1) as f64;
+    let degrees = indexed.adjacency.iter().map(|edges| edges.len() as f64 / others).collect();
+    indexed.scores(degrees)
+}
+
+/**
+ * In-Degree Centrality
+ *
+ * The number of incoming edges of each node divided by the `V - 1` other nodes.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V)
+ */
+pub fn in_degree_centrality<N: Hash + Eq + Clone>(graph: &Graph<N>) -> HashMap<N, f64> {
+    let indexed = Indexed::unweighted(graph);
+    let others = indexed.nodes.len().saturating_sub(1).max(1) as f64;
+    let mut degrees = vec![0.0; indexed.nodes.len()];
+    for edges in &indexed.adjacency {
+        for &(to, _) in edges {
+            degrees[to] += 1.0 / others;
+        }
+    }
+    indexed.scores(degrees)
+}
+
+/**
+ * Closeness Centrality
+ *
+ * For a node that reaches `r` other nodes with a total distance of `d`, the closeness is `r / d`,
+ * scaled by `r / (V - 1)` (Wasserman and Faust) so that nodes reaching only a small part of a
+ * disconnected graph do not get a high score. Distances are counted in edges along outgoing edges.
+ *
+ * Time Complexity: O(V * (V + E))
+ * Space Complexity: O(V)
+ */
+pub fn closeness_centrality<N: Hash + Eq + Clone>(graph: &Graph<N>) -> HashMap<N, f64> {
+    let indexed = Indexed::unweighted(graph);
+    let n = indexed.nodes.len();
+    let mut closeness = vec![0.0; n];
+    let mut distances = vec![usize::MAX; n];
+    let mut queue = VecDeque::new();
+
+    for (source, score) in closeness.iter_mut().enumerate() {
+        distances.fill(usize::MAX);
+        distances[source] = 0;
+        queue.push_back(source);
+        let (mut reached, mut total) = (0usize, 0usize);
+
+        while let Some(current) = queue.pop_front() {
+            for &(neighbor, _) in &indexed.adjacency[current] {
+                if distances[neighbor] == usize::MAX {
+                    distances[neighbor] = distances[current] + 1;
+                    reached += 1;
+                    total += distances[neighbor];
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        if total > 0 {
+            *score = (reached as f64 / total as f64) * (reached as f64 / (n - 1) as f64);
+        }
+    }
+
+    indexed.scores(closeness)
+}
+
+/**
+ * Betweenness Centrality (Brandes' algorithm, unweighted)
+ *
+ * The sum over all ordered pairs `(s, t)` of the fraction of shortest `s -> t` paths that pass
+ * through each node. Brandes' algorithm runs one breadth first search per source, counting the
+ * shortest paths to every node on the way out and accumulating the dependencies on the way back,
+ * instead of listing the paths.
+ *
+ * Undirected graphs store every edge twice, so each pair is counted in both directions; halve the
+ * scores for the usual undirected values.
+ *
+ * Time Complexity: O(V * E)
+ * Space Complexity: O(V + E)
+ */
+pub fn betweenness_centrality<N: Hash + Eq + Clone>(graph: &Graph<N>) -> HashMap<N, f64> {
+    let indexed = Indexed::unweighted(graph);
+    let n = indexed.nodes.len();
+    let mut betweenness = vec![0.0; n];
+    let mut state = BrandesState::new(n);
+    let mut queue = VecDeque::new();
+
+    for source in 0..n {
+        state.reset(source);
+        queue.push_back(source);
+
+        while let Some(current) = queue.pop_front() {
+            state.order.push(current);
+            for &(neighbor, _) in &indexed.adjacency[current] {
+                if state.distances[neighbor] == u64::MAX {
+                    state.distances[neighbor] = state.distances[current] + 1;
+                    queue.push_back(neighbor);
+                }
+                if state.distances[neighbor] == state.distances[current] + 1 {
+                    state.paths[neighbor] += state.paths[current];
+                    state.predecessors[neighbor].push(current);
+                }
+            }
+        }
+
+        state.accumulate(source, &mut betweenness);
+    }
+
+    indexed.scores(betweenness)
+}
+
+/**
+ * Betweenness Centrality (Brandes' algorithm, weighted)
+ *
+ * The same as `betweenness_centrality`, but shortest paths are by total weight. Each source runs the
+ * binary heap Dijkstra from `dijkstras_shortest_path_heap`, settling nodes in order of distance and
+ * counting every predecessor that reaches a node with the same lowest distance.
+ *
+ * Time Complexity: O(V * E log V)
+ * Space Complexity: O(V + E)
+ */
+pub fn weighted_betweenness_centrality<N: Hash + Eq + Clone>(graph: &WeightedGraph<N>) -> HashMap<N, f64> {
+    let indexed = Indexed::weighted(graph);
+    let n = indexed.nodes.len();
+    let mut betweenness = vec![0.0; n];
+    let mut state = BrandesState::new(n);
+    let mut settled = vec![false; n];
+    let mut heap = BinaryHeap::new();
+
+    for source in 0..n {
+        state.reset(source);
+        settled.fill(false);
+        heap.push(Reverse((0, source)));
+
+        while let Some(Reverse((cost, current))) = heap.pop() {
+            // Skip stale entries for nodes that were already settled
+            if settled[current] {
+                continue;
+            }
+            settled[current] = true;
+            state.order.push(current);
+
+            for &(neighbor, weight) in &indexed.adjacency[current] {
+                let next = cost + weight as u64;
+                if next < state.distances[neighbor] {
+                    state.distances[neighbor] = next;
+                    state.paths[neighbor] = 0.0;
+                    state.predecessors[neighbor].clear();
+                    heap.push(Reverse((next, neighbor)));
+                }
+                if next == state.distances[neighbor] && !settled[neighbor] {
+                    state.paths[neighbor] += state.paths[current];
+                    state.predecessors[neighbor].push(current);
+                }
+            }
+        }
+
+        state.accumulate(source, &mut betweenness);
+    }
+
+    indexed.scores(betweenness)
+}
+
+// Per-source bookkeeping for Brandes' algorithm, reused between sources.
+struct BrandesState {
+    distances: Vec<u64>,
+    // Number of shortest paths from the source
+    paths: Vec<f64>,
+    predecessors: Vec<Vec<usize>>,
+    // Nodes in the order they were settled, so dependencies can be summed farthest first
+    order: Vec<usize>,
+    dependency: Vec<f64>,
+}
+
+impl BrandesState {
+    fn new(n: usize) -> Self {
+        BrandesState {
+            distances: vec![u64::MAX; n],
+            paths: vec![0.0; n],
+            predecessors: vec![Vec::new(); n],
+            order: Vec::with_capacity(n),
+            dependency: vec![0.0; n],
+        }
+    }
+
+    fn reset(&mut self, source: usize) {
+        self.distances.fill(u64::MAX);
+        self.paths.fill(0.0);
+        self.dependency.fill(0.0);
+        self.predecessors.iter_mut().for_each(Vec::clear);
+        self.order.clear();
+        self.distances[source] = 0;
+        self.paths[source] = 1.0;
+    }
+
+    fn accumulate(&mut self, source: usize, betweenness: &mut [f64]) {
+        while let Some(node) = self.order.pop() {
+            for &predecessor in &self.predecessors[node] {
+                self.dependency[predecessor] +=
+                    self.paths[predecessor] / self.paths[node] * (1.0 + self.dependency[node]);
+            }
+            if node != source {
+                betweenness[node] += self.dependency[node];
+            }
+        }
+    }
+}
+
+/**
+ * PageRank
+ *
+ * Power iteration of the random surfer model: with probability `damping` the surfer follows a random
+ * outgoing edge, otherwise (or at a node without outgoing edges) it jumps to a random node. Iterates
+ * until the total change of all scores is below `tolerance`, or `max_iterations` is reached.
+ *
+ * The scores sum to 1.
+ *
+ * Time Complexity: O(k * (V + E)) for k iterations
+ * Space Complexity: O(V)
+ *
+ * @param graph A directed graph
+ * @param damping The probability of following an edge, usually 0.85
+ * @param tolerance Stop when the L1 distance between two iterations is below this
+ * @param max_iterations Stop after this many iterations even if not converged
+ */
+pub fn pagerank<N: Hash + Eq + Clone>(
+    graph: &Graph<N>,
+    damping: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> HashMap<N, f64> {
+    assert!((0.0..=1.0).contains(&damping), "damping must be a probability");
+    let indexed = Indexed::unweighted(graph);
+    let n = indexed.nodes.len();
+    if n == 0 {
+        return HashMap::new();
+    }
+
+    let mut rank = vec![1.0 / n as f64; n];
+    let mut next = vec![0.0; n];
+    for _ in 0..max_iterations {
+        // Rank held by nodes without outgoing edges is spread over every node
+        let dangling: f64 = (0..n).filter(|&node| indexed.adjacency[node].is_empty()).map(|node| rank[node]).sum();
+        next.fill((1.0 - damping + damping * dangling) / n as f64);
+
+        for (node, edges) in indexed.adjacency.iter().enumerate() {
+            let share = damping * rank[node] / edges.len() as f64;
+            for &(to, _) in edges {
+                next[to] += share;
+            }
+        }
+
+        let change: f64 = rank.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+        std::mem::swap(&mut rank, &mut next);
+        if change < tolerance {
+            break;
+        }
+    }
+
+    indexed.scores(rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+    }
+
+    // A - B - C - D as an undirected path
+    fn create_path_graph() -> Graph<char> {
+        let mut graph = Graph::new();
+        graph.insert('A', vec!['B']);
+        graph.insert('B', vec!['A', 'C']);
+        graph.insert('C', vec!['B', 'D']);
+        graph.insert('D', vec!['C']);
+        graph
+    }
+
+    #[test]
+    fn test_degree_and_closeness_centrality() {
+        let graph = create_path_graph();
+        let degree = degree_centrality(&graph);
+        assert_close(degree[&'A'], 1.0 / 3.0);
+        assert_close(degree[&'B'], 2.0 / 3.0);
+        assert_close(in_degree_centrality(&graph)[&'B'], 2.0 / 3.0);
+
+        let closeness = closeness_centrality(&graph);
+        // A reaches B, C, D at distances 1, 2, 3
+        assert_close(closeness[&'A'], 3.0 / 6.0);
+        assert_close(closeness[&'B'], 3.0 / 4.0);
+    }
+
+    #[test]
+    fn test_betweenness_centrality() {
+        let graph = create_path_graph();
+        let betweenness = betweenness_centrality(&graph);
+        // B is on the paths A-C and A-D, in both directions
+        assert_close(betweenness[&'A'], 0.0);
+        assert_close(betweenness[&'B'], 4.0);
+        assert_close(betweenness[&'C'], 4.0);
+
+        // Two equally short routes from S to T split the credit
+        let mut weighted = WeightedGraph::new();
+        weighted.insert('S', HashMap::from([('X', 1), ('Y', 2), ('Z', 1)]));
+        weighted.insert('X', HashMap::from([('T', 2)]));
+        weighted.insert('Y', HashMap::from([('T', 1)]));
+        weighted.insert('Z', HashMap::from([('T', 5)]));
+        let betweenness = weighted_betweenness_centrality(&weighted);
+        assert_close(betweenness[&'X'], 0.5);
+        assert_close(betweenness[&'Y'], 0.5);
+        assert_close(betweenness[&'Z'], 0.0);
+        assert_close(betweenness[&'T'], 0.0);
+    }
+
+    #[test]
+    fn test_pagerank() {
+        // Every node links to C, and C links back to A
+        let mut graph = Graph::new();
+        graph.insert('A', vec!['C']);
+        graph.insert('B', vec!['C']);
+        graph.insert('C', vec!['A']);
+        graph.insert('D', vec!['C']);
+        let rank = pagerank(&graph, 0.85, 1e-10, 100);
+        assert_close(rank.values().sum(), 1.0);
+        assert!(rank[&'C'] > rank[&'A']);
+        assert!(rank[&'A'] > rank[&'B']);
+        assert_close(rank[&'B'], rank[&'D']);
+        assert_close(rank[&'B'], 0.15 / 4.0);
+
+        // On the undirected path both ends are equally important, and less than the middle
+        let rank = pagerank(&create_path_graph(), 0.85, 1e-10, 100);
+        assert_close(rank[&'A'], rank[&'D']);
+        assert!(rank[&'B'] > rank[&'A']);
+    }
+}
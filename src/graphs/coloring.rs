@@ -0,0 +1,390 @@
+/**
+ * Graph Coloring
+ *
+ * Assign a color (numbered from 0) to every node so that no two neighbors share a color, using as
+ * few colors as possible. Scheduling exams so no student has two at once, or assigning radio
+ * frequencies so nearby transmitters do not interfere, are both coloring problems.
+ *
+ * Finding the minimum number of colors (the chromatic number) is NP-hard, so there are fast
+ * heuristics and an exact solver for small graphs:
+ * - `greedy_coloring` gives each node, in a chosen order, the smallest color its neighbors do not use.
+ * - `dsatur` always colors the node whose neighbors already use the most distinct colors next.
+ * - `chromatic_number` searches for an optimal coloring with backtracking.
+ *
+ * The graphs are undirected and edges may be listed in one or both directions. Self loops are ignored.
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+type Graph<N> = HashMap<N, Vec<N>>;
+
+const UNCOLORED: usize = usize::MAX;
+
+/**
+ * The order in which `greedy_coloring` visits the nodes.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GreedyOrder {
+    /// Sorted node order.
+    Natural,
+    /// Highest degree first (Welsh-Powell).
+    LargestFirst,
+    /// Repeatedly remove a node of smallest remaining degree and color in the reverse of the removal
+    /// order, so every node has few already colored neighbors when it is colored.
+    SmallestLast,
+}
+
+// The undirected graph with its nodes numbered in sorted order.
+struct Indexed<N> {
+    nodes: Vec<N>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl<N: Hash + Eq + Clone + Ord> Indexed<N> {
+    fn new(graph: &Graph<N>) -> Self {
+        let mut nodes: Vec<N> = graph.keys().chain(graph.values().flatten()).cloned().collect();
+        nodes.sort();
+        nodes.dedup();
+        let index: HashMap<&N, usize> = nodes.iter().enumerate().map(|(i, node)| (node, i)).collect();
+
+        let mut adjacency = vec![Vec::new(); nodes.len()];
+        for (from, neighbors) in graph {
+            for to in neighbors {
+                let (from, to) = (index[from], index[to]);
+                if from != to {
+                    adjacency[from].push(to);
+                    adjacency[to].push(from);
+                }
+            }
+        }
+        for neighbors in adjacency.iter_mut() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+
+        Indexed { nodes, adjacency }
+    }
+
+    fn to_coloring(&self, colors: &[usize]) -> HashMap<N, usize> {
+        self.nodes.iter().cloned().zip(colors.iter().copied()).collect()
+    }
+
+    // The smallest color not used by a neighbor of `node`.
+    fn smallest_free_color(&self, node: usize, colors: &[usize]) -> usize {
+        let mut used = vec![false; self.adjacency[node].len() + 1];
+        for &neighbor in &self.adjacency[node] {
+            if colors[neighbor] < used.len() {
+                used[colors[neighbor]] = true;
+            }
+        }
+        used.iter().position(|&used| !used).unwrap()
+    }
+
+    fn smallest_last_order(&self) -> Vec<usize> {
+        let n = self.nodes.len();
+        let mut degree: Vec<usize> = self.adjacency.iter().map(Vec::len).collect();
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        for _ in 0..n {
+            let node = (0..n).filter(|&node| !removed[node]).min_by_key(|&node| degree[node]).unwrap();
+            removed[node] = true;
+            order.push(node);
+            for &neighbor in &self.adjacency[node] {
+                degree[neighbor] -= 1;
+            }
+        }
+        order.reverse();
+        order
+    }
+}
+
+/**
+ * Greedy Coloring
+ *
+ * Visits the nodes in the given order and gives each one the smallest color none of its neighbors
+ * has. Uses at most `max degree + 1` colors.
+ *
+ * Greedy Algorithm
+ *
+ * Time Complexity: O(V^2) for SmallestLast, O(V log V + E) otherwise
+ * Space Complexity: O(V + E)
+ *
+ * @param graph An undirected graph
+ * @param order The order in which nodes are colored
+ * @return The color of every node
+ */
+pub fn greedy_coloring<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>, order: GreedyOrder) -> HashMap<N, usize> {
+    let indexed = Indexed::new(graph);
+    let n = indexed.nodes.len();
+    let order: Vec<usize> = match order {
+        GreedyOrder::Natural => (0..n).collect(),
+        GreedyOrder::LargestFirst => {
+            let mut order: Vec<usize> = (0..n).collect();
+            order.sort_by_key(|&node| std::cmp::Reverse(indexed.adjacency[node].len()));
+            order
+        }
+        GreedyOrder::SmallestLast => indexed.smallest_last_order(),
+    };
+
+    let mut colors = vec![UNCOLORED; n];
+    for node in order {
+        colors[node] = indexed.smallest_free_color(node, &colors);
+    }
+    indexed.to_coloring(&colors)
+}
+
+/**
+ * DSatur (Degree of Saturation) Coloring
+ *
+ * The saturation of a node is the number of distinct colors among its neighbors. DSatur always
+ * colors the uncolored node with the highest saturation next (ties go to the highest degree) with
+ * the smallest free color. Nodes that are running out of options are dealt with first, which
+ * usually needs fewer colors than a fixed order, and is optimal on bipartite graphs.
+ *
+ * Greedy Algorithm
+ *
+ * Time Complexity: O(V^2 + E)
+ * Space Complexity: O(V + E)
+ *
+ * @param graph An undirected graph
+ * @return The color of every node
+ */
+pub fn dsatur<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> HashMap<N, usize> {
+    let indexed = Indexed::new(graph);
+    indexed.to_coloring(&dsatur_colors(&indexed))
+}
+
+fn dsatur_colors<N>(indexed: &Indexed<N>) -> Vec<usize> {
+    let n = indexed.nodes.len();
+    let mut colors = vec![UNCOLORED; n];
+    // For every node, which colors its neighbors use
+    let mut neighbor_colors: Vec<Vec<bool>> = vec![Vec::new(); n];
+    let mut saturation = vec![0; n];
+
+    for _ in 0..n {
+        let node = (0..n)
+            .filter(|&node| colors[node] == UNCOLORED)
+            .max_by_key(|&node| (saturation[node], indexed.adjacency[node].len(), std::cmp::Reverse(node)))
+            .unwrap();
+        let color = neighbor_colors[node].iter().position(|&used| !used).unwrap_or(neighbor_colors[node].len());
+        colors[node] = color;
+
+        for &neighbor in &indexed.adjacency[node] {
+            let used = &mut neighbor_colors[neighbor];
+            if used.len() <= color {
+                used.resize(color + 1, false);
+            }
+            if !used[color] {
+                used[color] = true;
+                saturation[neighbor] += 1;
+            }
+        }
+    }
+
+    colors
+}
+
+/**
+ * Exact Chromatic Number
+ *
+ * Backtracking search over colorings, always branching on the most saturated node like DSatur. The
+ * DSatur coloring is the first upper bound, and any branch that would need as many colors as the
+ * best coloring found so far is cut. The search stops early when it matches the size of a greedily
+ * found clique, since a clique of `k` nodes needs `k` colors.
+ *
+ * The worst case is exponential, so this is meant for graphs of up to about 50 nodes.
+ *
+ * Backtracking, Branch and Bound
+ *
+ * Time Complexity: O(k^V) worst case for k colors
+ * Space Complexity: O(V + E)
+ *
+ * @param graph An undirected graph
+ * @return The chromatic number and an optimal coloring
+ */
+pub fn chromatic_number<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> (usize, HashMap<N, usize>) {
+    let indexed = Indexed::new(graph);
+    let best = dsatur_colors(&indexed);
+    let best_count = best.iter().map(|&color| color + 1).max().unwrap_or(0);
+
+    let mut search = ExactSearch {
+        adjacency: &indexed.adjacency,
+        colors: vec![UNCOLORED; indexed.nodes.len()],
+        best,
+        best_count,
+        lower_bound: greedy_clique_size(&indexed.adjacency),
+    };
+    search.search(0, 0);
+
+    (search.best_count, indexed.to_coloring(&search.best))
+}
+
+struct ExactSearch<'a> {
+    adjacency: &'a [Vec<usize>],
+    colors: Vec<usize>,
+    best: Vec<usize>,
+    best_count: usize,
+    lower_bound: usize,
+}
+
+impl ExactSearch<'_> {
+    fn search(&mut self, colored: usize, used: usize) {
+        if colored == self.colors.len() {
+            if used < self.best_count {
+                self.best_count = used;
+                self.best.copy_from_slice(&self.colors);
+            }
+            return;
+        }
+
+        // Branch on the uncolored node with the most distinct neighbor colors
+        let mut node = UNCOLORED;
+        let mut node_key = (0, 0);
+        let mut forbidden = Vec::new();
+        for candidate in 0..self.colors.len() {
+            if self.colors[candidate] != UNCOLORED {
+                continue;
+            }
+            let mut seen = vec![false; used];
+            for &neighbor in &self.adjacency[candidate] {
+                if self.colors[neighbor] != UNCOLORED {
+                    seen[self.colors[neighbor]] = true;
+                }
+            }
+            let key = (seen.iter().filter(|&&seen| seen).count(), self.adjacency[candidate].len());
+            if node == UNCOLORED || key > node_key {
+                node = candidate;
+                node_key = key;
+                forbidden = seen;
+            }
+        }
+
+        // Colors 0..used are reused, color `used` opens a new one; never reach the best count.
+        for color in 0..=used {
+            if forbidden.get(color) == Some(&true) {
+                continue;
+            }
+            let next_used = used.max(color + 1);
+            if next_used >= self.best_count {
+                break;
+            }
+            self.colors[node] = color;
+            self.search(colored + 1, next_used);
+            self.colors[node] = UNCOLORED;
+            if self.best_count <= self.lower_bound {
+                return;
+            }
+        }
+    }
+}
+
+// Size of a clique grown greedily from each node, a cheap lower bound on the chromatic number.
+fn greedy_clique_size(adjacency: &[Vec<usize>]) -> usize {
+    let mut best = 0;
+    for start in 0..adjacency.len() {
+        let mut clique = vec![start];
+        let mut candidates = adjacency[start].clone();
+        candidates.sort_by_key(|&node| std::cmp::Reverse(adjacency[node].len()));
+        for candidate in candidates {
+            if clique.iter().all(|member| adjacency[candidate].binary_search(member).is_ok()) {
+                clique.push(candidate);
+            }
+        }
+        best = best.max(clique.len());
+    }
+    best
+}
+
+/**
+ * Check that every node has a color and no edge joins two nodes of the same color.
+ */
+pub fn is_valid_coloring<N: Hash + Eq>(graph: &Graph<N>, coloring: &HashMap<N, usize>) -> bool {
+    graph.iter().all(|(from, neighbors)| {
+        coloring.contains_key(from)
+            && neighbors.iter().all(|to| {
+                to == from || coloring.get(to).is_some_and(|color| *color != coloring[from])
+            })
+    })
+}
+
+/**
+ * The number of distinct colors used by a coloring.
+ */
+pub fn color_count<N>(coloring: &HashMap<N, usize>) -> usize {
+    let mut colors: Vec<usize> = coloring.values().copied().collect();
+    colors.sort_unstable();
+    colors.dedup();
+    colors.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(n: u32) -> Graph<u32> {
+        (0..n).map(|node| (node, vec![(node + 1) % n])).collect()
+    }
+
+    // Two rows of n nodes where u_i and v_j are joined unless i == j. Bipartite, but greedy in
+    // natural order alternates rows and needs n colors.
+    fn crown(n: u32) -> Graph<u32> {
+        let mut graph = Graph::new();
+        for i in 0..n {
+            let u = 2 * i;
+            graph.insert(u, (0..n).filter(|&j| j != i).map(|j| 2 * j + 1).collect());
+        }
+        graph
+    }
+
+    fn petersen() -> Graph<u32> {
+        let mut graph = Graph::new();
+        for i in 0..5 {
+            graph.insert(i, vec![(i + 1) % 5, i + 5]);
+            graph.insert(i + 5, vec![(i + 2) % 5 + 5]);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_heuristics_are_valid() {
+        for graph in [cycle(5), cycle(6), crown(5), petersen()] {
+            for order in [GreedyOrder::Natural, GreedyOrder::LargestFirst, GreedyOrder::SmallestLast] {
+                assert!(is_valid_coloring(&graph, &greedy_coloring(&graph, order)));
+            }
+            assert!(is_valid_coloring(&graph, &dsatur(&graph)));
+        }
+
+        assert_eq!(color_count(&greedy_coloring(&crown(5), GreedyOrder::Natural)), 5);
+        assert_eq!(color_count(&dsatur(&crown(5))), 2);
+        assert_eq!(color_count(&dsatur(&cycle(6))), 2);
+    }
+
+    #[test]
+    fn test_chromatic_number() {
+        assert_eq!(chromatic_number(&cycle(5)).0, 3);
+        assert_eq!(chromatic_number(&cycle(6)).0, 2);
+        assert_eq!(chromatic_number(&crown(6)).0, 2);
+        assert_eq!(chromatic_number(&petersen()).0, 3);
+
+        let complete: Graph<u32> = (0..6).map(|node| (node, (0..6).filter(|&to| to != node).collect())).collect();
+        let (count, coloring) = chromatic_number(&complete);
+        assert_eq!(count, 6);
+        assert!(is_valid_coloring(&complete, &coloring));
+        assert_eq!(color_count(&coloring), 6);
+
+        let (count, coloring) = chromatic_number(&petersen());
+        assert!(is_valid_coloring(&petersen(), &coloring));
+        assert_eq!(color_count(&coloring), count);
+    }
+
+    #[test]
+    fn test_is_valid_coloring() {
+        let graph = cycle(4);
+        let mut coloring = HashMap::from([(0, 0), (1, 1), (2, 0), (3, 1)]);
+        assert!(is_valid_coloring(&graph, &coloring));
+        coloring.insert(3, 0);
+        assert!(!is_valid_coloring(&graph, &coloring));
+        coloring.remove(&3);
+        assert!(!is_valid_coloring(&graph, &coloring));
+    }
+}
@@ -0,0 +1,241 @@
+/**
+ * Connected Components
+ *
+ * Splits a graph into the groups of nodes that can reach each other.
+ *
+ * - `connected_components` labels an undirected graph, stored (like the graphs in `bfs_graph`) as
+ *   adjacency lists that contain every edge in both directions.
+ * - `weakly_connected_components` labels a directed graph as if every edge could be followed both
+ *   ways.
+ * - `IncrementalConnectivity` keeps the components up to date while edges are added one at a time,
+ *   backed by the `UnionFind` in `data_structures`.
+ *
+ * Component labels are numbered from 0 in the order the components are discovered.
+ */
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::data_structures::union_find::UnionFind;
+
+type Graph<N> = HashMap<N, Vec<N>>;
+
+/**
+ * Label the connected components of an undirected graph with a breadth first search from every
+ * node that has not been labeled yet.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V)
+ *
+ * @param graph An undirected graph, with every edge listed in both directions
+ * @return The component label of every node
+ */
+pub fn connected_components<N: Hash + Eq + Clone>(graph: &Graph<N>) -> HashMap<N, usize> {
+    label_components(graph, |node| graph.get(node).into_iter().flatten())
+}
+
+/**
+ * Label the weakly connected components of a directed graph.
+ *
+ * The reverse of every edge is added first so the search can follow edges in both directions.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V + E)
+ *
+ * @param graph A directed graph
+ * @return The component label of every node
+ */
+pub fn weakly_connected_components<N: Hash + Eq + Clone>(graph: &Graph<N>) -> HashMap<N, usize> {
+    let mut reverse: Graph<N> = HashMap::new();
+    for (from, neighbors) in graph {
+        for to in neighbors {
+            reverse.entry(to.clone()).or_default().push(from.clone());
+        }
+    }
+
+    label_components(graph, |node| {
+        graph.get(node).into_iter().flatten().chain(reverse.get(node).into_iter().flatten())
+    })
+}
+
+fn label_components<'a, N, F, I>(graph: &'a Graph<N>, neighbors: F) -> HashMap<N, usize>
+where
+    N: Hash + Eq + Clone + 'a,
+    F: Fn(&N) -> I,
+    I: Iterator<Item = &'a N>,
+{
+    let mut labels = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut next_label = 0;
+
+    for start in graph.keys() {
+        if labels.contains_key(start) {
+            continue;
+        }
+
+        labels.insert(start.clone(), next_label);
+        queue.push_back(start);
+        while let Some(current) = queue.pop_front() {
+            for neighbor in neighbors(current) {
+                if !labels.contains_key(neighbor) {
+                    labels.insert(neighbor.clone(), next_label);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        next_label += 1;
+    }
+
+    labels
+}
+
+/**
+ * Incremental Connectivity
+ *
+ * Answers "are `a` and `b` connected?" on an undirected graph that only grows. Nodes are mapped to
+ * dense indices of a `UnionFind`, so adding an edge and asking a query both take near constant
+ * amortized time, without ever searching the graph.
+ *
+ * Time Complexity: O(α(n)) amortized per edge or query
+ * Space Complexity: O(V)
+ */
+pub struct IncrementalConnectivity<N> {
+    index: HashMap<N, usize>,
+    sets: UnionFind,
+}
+
+impl<N: Hash + Eq + Clone> Default for IncrementalConnectivity<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Hash + Eq + Clone> IncrementalConnectivity<N> {
+    pub fn new() -> Self {
+        IncrementalConnectivity { index: HashMap::new(), sets: UnionFind::new(0) }
+    }
+
+    /**
+     * Start from every edge of an existing undirected or directed graph.
+     */
+    pub fn from_graph(graph: &Graph<N>) -> Self {
+        let mut connectivity = Self::new();
+        for (from, neighbors) in graph {
+            connectivity.add_node(from.clone());
+            for to in neighbors {
+                connectivity.add_edge(from.clone(), to.clone());
+            }
+        }
+        connectivity
+    }
+
+    /**
+     * Add a node without any edges. Adding a node that already exists does nothing.
+     */
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.index.get(&node) {
+            return index;
+        }
+        let index = self.sets.add();
+        self.index.insert(node, index);
+        index
+    }
+
+    /**
+     * Add an undirected edge, adding its nodes if they are new.
+     *
+     * Returns true if the edge joined two previously separate components.
+     */
+    pub fn add_edge(&mut self, a: N, b: N) -> bool {
+        let a = self.add_node(a);
+        let b = self.add_node(b);
+        self.sets.union(a, b)
+    }
+
+    /**
+     * Check whether there is a path between `a` and `b`. Unknown nodes are not connected to anything.
+     */
+    pub fn connected(&mut self, a: &N, b: &N) -> bool {
+        match (self.index.get(a), self.index.get(b)) {
+            (Some(&a), Some(&b)) => self.sets.connected(a, b),
+            _ => false,
+        }
+    }
+
+    /**
+     * Number of nodes in the component of `node`, 0 if the node is unknown.
+     */
+    pub fn component_size(&mut self, node: &N) -> usize {
+        match self.index.get(node) {
+            Some(&node) => self.sets.set_size(node),
+            None => 0,
+        }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.sets.set_count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_undirected_graph() -> Graph<char> {
+        let mut graph = Graph::new();
+        graph.insert('A', vec!['B']);
+        graph.insert('B', vec!['A', 'C']);
+        graph.insert('C', vec!['B']);
+        graph.insert('D', vec!['E']);
+        graph.insert('E', vec!['D']);
+        graph.insert('F', vec![]);
+        graph
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let labels = connected_components(&create_undirected_graph());
+        assert_eq!(labels.len(), 6);
+        assert_eq!(labels[&'A'], labels[&'C']);
+        assert_eq!(labels[&'D'], labels[&'E']);
+        assert_ne!(labels[&'A'], labels[&'D']);
+        assert_ne!(labels[&'F'], labels[&'A']);
+        assert_ne!(labels[&'F'], labels[&'D']);
+        let mut distinct: Vec<usize> = labels.values().copied().collect();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_weakly_connected_components() {
+        let mut graph = Graph::new();
+        graph.insert('A', vec!['B']);
+        graph.insert('C', vec!['B']);
+        graph.insert('D', vec!['E']);
+        // 'B' and 'E' only appear as edge targets
+        let labels = weakly_connected_components(&graph);
+        assert_eq!(labels.len(), 5);
+        assert_eq!(labels[&'A'], labels[&'C']);
+        assert_eq!(labels[&'A'], labels[&'B']);
+        assert_eq!(labels[&'D'], labels[&'E']);
+        assert_ne!(labels[&'A'], labels[&'D']);
+    }
+
+    #[test]
+    fn test_incremental_connectivity() {
+        let mut connectivity = IncrementalConnectivity::from_graph(&create_undirected_graph());
+        assert_eq!(connectivity.component_count(), 3);
+        assert!(connectivity.connected(&'A', &'C'));
+        assert!(!connectivity.connected(&'A', &'E'));
+        assert!(!connectivity.connected(&'A', &'Z'));
+
+        assert!(connectivity.add_edge('C', 'D'));
+        assert!(!connectivity.add_edge('A', 'E'));
+        assert!(connectivity.connected(&'A', &'E'));
+        assert_eq!(connectivity.component_size(&'E'), 5);
+
+        connectivity.add_edge('F', 'Z');
+        assert_eq!(connectivity.component_count(), 2);
+        assert_eq!(connectivity.component_size(&'Z'), 2);
+    }
+}
@@ -0,0 +1,332 @@
+/**
+ * Multi-Criteria and Constrained Shortest Paths
+ *
+ * Edges carry `K` non-negative weights instead of the single `u32` of `dijkstras_shortest_path_heap`,
+ * for example `[minutes, toll]`. Paths are compared by their total of every weight.
+ *
+ * - `lexicographic_shortest_path` minimizes the first weight, breaks ties with the second and so on.
+ *   Arrays compare lexicographically, so this is Dijkstra's algorithm with `[u64; K]` costs.
+ * - `pareto_paths` lists every Pareto-optimal path: those where no other path is at least as good in
+ *   every weight and better in one. Every node keeps a set ("front") of non-dominated labels instead
+ *   of a single distance (Martins' label-setting algorithm).
+ * - `constrained_shortest_path` minimizes the first weight while the second stays within a budget,
+ *   e.g. the fastest route with at most 10 in tolls. It is the same label-setting search, dropping
+ *   labels over budget and stopping at the first label that reaches the target.
+ *
+ * Labels are taken from the heap in lexicographic order, so a label can only be dominated by labels
+ * that were already made permanent: each one is checked once when it is popped.
+ *
+ * Time Complexity: O(L * (log L + D * F)) for L labels created, out-degree D and front size F.
+ * The number of Pareto-optimal paths, and therefore L, can grow exponentially with the graph.
+ * Space Complexity: O(L)
+ */
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+type Graph<N, const K: usize> = HashMap<N, HashMap<N, [u32; K]>>;
+
+/**
+ * A path with the totals of its `K` weights.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<N, const K: usize> {
+    pub nodes: Vec<N>,
+    pub costs: [u64; K],
+}
+
+struct Label<N, const K: usize> {
+    node: N,
+    costs: [u64; K],
+    parent: Option<usize>,
+}
+
+// `a` is at least as good as `b` in every weight
+fn covers<const K: usize>(a: &[u64; K], b: &[u64; K]) -> bool {
+    a.iter().zip(b).all(|(a, b)| a <= b)
+}
+
+fn add<const K: usize>(costs: &[u64; K], weights: &[u32; K]) -> [u64; K] {
+    std::array::from_fn(|i| costs[i] + weights[i] as u64)
+}
+
+struct LabelSearch<'a, N, const K: usize> {
+    graph: &'a Graph<N, K>,
+    labels: Vec<Label<N, K>>,
+    // The permanent labels of every node
+    fronts: HashMap<N, Vec<usize>>,
+    heap: BinaryHeap<Reverse<([u64; K], usize)>>,
+}
+
+impl<'a, N: Hash + Eq + Clone, const K: usize> LabelSearch<'a, N, K> {
+    fn new(graph: &'a Graph<N, K>, start: &N) -> Self {
+        let labels = vec![Label { node: start.clone(), costs: [0; K], parent: None }];
+        LabelSearch { graph, labels, fronts: HashMap::new(), heap: BinaryHeap::from([Reverse(([0; K], 0))]) }
+    }
+
+    fn is_covered(&self, node: &N, costs: &[u64; K]) -> bool {
+        let front = self.fronts.get(node).map_or(&[][..], |front| &front[..]);
+        front.iter().any(|&label| covers(&self.labels[label].costs, costs))
+    }
+
+    /**
+     * Pop labels until one is not covered by the front of its node, make it permanent and extend it
+     * along every edge. `keep` decides which new labels are worth queuing.
+     */
+    fn next_permanent<F>(&mut self, target: &N, mut keep: F) -> Option<usize>
+    where
+        F: FnMut(&[u64; K]) -> bool,
+    {
+        while let Some(Reverse((costs, label))) = self.heap.pop() {
+            let node = self.labels[label].node.clone();
+            if self.is_covered(&node, &costs) {
+                continue;
+            }
+            self.fronts.entry(node.clone()).or_default().push(label);
+
+            for (neighbor, weights) in self.graph.get(&node).into_iter().flatten() {
+                let next = add(&costs, weights);
+                // Anything covered by a path that already reached the target cannot be Pareto-optimal
+                if !keep(&next) || self.is_covered(neighbor, &next) || self.is_covered(target, &next) {
+                    continue;
+                }
+                self.labels.push(Label { node: neighbor.clone(), costs: next, parent: Some(label) });
+                self.heap.push(Reverse((next, self.labels.len() - 1)));
+            }
+            return Some(label);
+        }
+        None
+    }
+
+    fn path(&self, mut label: usize) -> Path<N, K> {
+        let costs = self.labels[label].costs;
+        let mut nodes = vec![self.labels[label].node.clone()];
+        while let Some(parent) = self.labels[label].parent {
+            nodes.push(self.labels[parent].node.clone());
+            label = parent;
+        }
+        nodes.reverse();
+        Path { nodes, costs }
+    }
+}
+
+/**
+ * Lexicographic Multi-Objective Dijkstra's Algorithm
+ *
+ * Time Complexity: O((V + E) log V * K)
+ * Space Complexity: O(V * K)
+ *
+ * @param graph A graph with `K` weights on every edge
+ * @param start The start node
+ * @param target The target node
+ * @return The path that is lexicographically cheapest, if there is one
+ */
+pub fn lexicographic_shortest_path<N, const K: usize>(
+    graph: &Graph<N, K>,
+    start: &N,
+    target: &N,
+) -> Option<Path<N, K>>
+where
+    N: Hash + Eq + Clone,
+{
+    let mut ids = vec![start.clone()];
+    let mut index = HashMap::from([(start.clone(), 0)]);
+    let mut distances = vec![[0u64; K]];
+    let mut predecessors = vec![usize::MAX];
+    let mut settled = vec![false];
+    let mut heap = BinaryHeap::from([Reverse(([0u64; K], 0))]);
+
+    while let Some(Reverse((costs, node))) = heap.pop() {
+        if settled[node] {
+            continue;
+        }
+        settled[node] = true;
+        if ids[node] == *target {
+            let mut nodes = vec![ids[node].clone()];
+            let mut current = node;
+            while predecessors[current] != usize::MAX {
+                current = predecessors[current];
+                nodes.push(ids[current].clone());
+            }
+            nodes.reverse();
+            return Some(Path { nodes, costs });
+        }
+
+        for (neighbor, weights) in graph.get(&ids[node]).into_iter().flatten() {
+            let next = add(&costs, weights);
+            let neighbor = *index.entry(neighbor.clone()).or_insert_with(|| {
+                ids.push(neighbor.clone());
+                distances.push([u64::MAX; K]);
+                predecessors.push(usize::MAX);
+                settled.push(false);
+                ids.len() - 1
+            });
+            if next < distances[neighbor] {
+                distances[neighbor] = next;
+                predecessors[neighbor] = node;
+                heap.push(Reverse((next, neighbor)));
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * Find every Pareto-optimal path from `start` to `target`. Paths with identical costs are reported
+ * once.
+ *
+ * @param graph A graph with `K` weights on every edge
+ * @param start The start node
+ * @param target The target node
+ * @return The Pareto-optimal paths, in lexicographic order of their costs
+ */
+pub fn pareto_paths<N, const K: usize>(graph: &Graph<N, K>, start: &N, target: &N) -> Vec<Path<N, K>>
+where
+    N: Hash + Eq + Clone,
+{
+    let mut search = LabelSearch::new(graph, start);
+    let mut paths = Vec::new();
+    while let Some(label) = search.next_permanent(target, |_| true) {
+        if search.labels[label].node == *target {
+            paths.push(search.path(label));
+        }
+    }
+    paths
+}
+
+/**
+ * Resource Constrained Shortest Path
+ *
+ * Every edge has `[cost, resource]`. Minimizes the total cost among the paths whose total resource
+ * is at most `budget`, using the least resource among equally cheap paths.
+ *
+ * @param graph A graph with a cost and a resource on every edge
+ * @param start The start node
+ * @param target The target node
+ * @param budget The maximum total resource
+ * @return The cheapest path within the budget, if there is one
+ */
+pub fn constrained_shortest_path<N>(graph: &Graph<N, 2>, start: &N, target: &N, budget: u64) -> Option<Path<N, 2>>
+where
+    N: Hash + Eq + Clone,
+{
+    let mut search = LabelSearch::new(graph, start);
+    while let Some(label) = search.next_permanent(target, |costs| costs[1] <= budget) {
+        if search.labels[label].node == *target {
+            return Some(search.path(label));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::Rng;
+
+    // [minutes, toll]
+    fn create_road_graph() -> Graph<char, 2> {
+        let mut graph = Graph::new();
+        graph.insert('A', HashMap::from([('B', [10, 0]), ('C', [4, 6]), ('D', [6, 2])]));
+        graph.insert('B', HashMap::from([('E', [10, 0])]));
+        graph.insert('C', HashMap::from([('E', [3, 5])]));
+        graph.insert('D', HashMap::from([('E', [6, 2]), ('C', [1, 1])]));
+        graph
+    }
+
+    #[test]
+    fn test_road_tolls() {
+        let graph = create_road_graph();
+        let paths = pareto_paths(&graph, &'A', &'E');
+        let fronts: Vec<(Vec<char>, [u64; 2])> = paths.into_iter().map(|path| (path.nodes, path.costs)).collect();
+        assert_eq!(
+            fronts,
+            vec![
+                (vec!['A', 'C', 'E'], [7, 11]),
+                (vec!['A', 'D', 'C', 'E'], [10, 8]),
+                (vec!['A', 'D', 'E'], [12, 4]),
+                (vec!['A', 'B', 'E'], [20, 0]),
+            ]
+        );
+
+        let fastest = lexicographic_shortest_path(&graph, &'A', &'E').unwrap();
+        assert_eq!(fastest.costs, [7, 11]);
+        assert_eq!(constrained_shortest_path(&graph, &'A', &'E', 8).unwrap().nodes, vec!['A', 'D', 'C', 'E']);
+        assert_eq!(constrained_shortest_path(&graph, &'A', &'E', 7).unwrap().costs, [12, 4]);
+        assert_eq!(constrained_shortest_path(&graph, &'A', &'E', 0).unwrap().costs, [20, 0]);
+        assert_eq!(constrained_shortest_path(&graph, &'E', &'A', 100), None);
+        assert!(pareto_paths(&graph, &'E', &'A').is_empty());
+    }
+
+    // All simple paths from `node` to `target` with their costs
+    fn enumerate<const K: usize>(
+        graph: &Graph<u32, K>,
+        node: u32,
+        target: u32,
+        path: &mut Vec<u32>,
+        costs: [u64; K],
+        out: &mut Vec<[u64; K]>,
+    ) {
+        if node == target {
+            out.push(costs);
+            return;
+        }
+        for (&neighbor, weights) in graph.get(&node).into_iter().flatten() {
+            if !path.contains(&neighbor) {
+                path.push(neighbor);
+                enumerate(graph, neighbor, target, path, add(&costs, weights), out);
+                path.pop();
+            }
+        }
+    }
+
+    fn random_graph<const K: usize>(rng: &mut Rng, n: u32, edges: usize) -> Graph<u32, K> {
+        let mut graph = Graph::new();
+        for _ in 0..edges {
+            let (from, to) = (rng.below(n as u64) as u32, rng.below(n as u64) as u32);
+            let weights: [u32; K] = std::array::from_fn(|_| rng.below(10) as u32);
+            graph.entry(from).or_insert_with(HashMap::new).insert(to, weights);
+        }
+        graph
+    }
+
+    fn check_against_enumeration<const K: usize>(seed: u64) {
+        let mut rng = Rng::new(seed);
+        let graph: Graph<u32, K> = random_graph(&mut rng, 8, 24);
+        let mut all = Vec::new();
+        enumerate(&graph, 0, 7, &mut vec![0], [0; K], &mut all);
+
+        let dominated = |costs: &[u64; K]| all.iter().any(|other| other != costs && covers(other, costs));
+        let mut expected: Vec<[u64; K]> = all.iter().filter(|costs| !dominated(costs)).copied().collect();
+        expected.sort();
+        expected.dedup();
+        let paths = pareto_paths(&graph, &0, &7);
+        assert_eq!(paths.iter().map(|path| path.costs).collect::<Vec<_>>(), expected);
+
+        for path in &paths {
+            let total = path.nodes.windows(2).fold([0; K], |costs, edge| add(&costs, &graph[&edge[0]][&edge[1]]));
+            assert_eq!(total, path.costs);
+        }
+        assert_eq!(lexicographic_shortest_path(&graph, &0, &7).map(|path| path.costs), expected.first().copied());
+    }
+
+    #[test]
+    fn test_matches_enumeration() {
+        for seed in 0..20 {
+            check_against_enumeration::<2>(seed);
+            check_against_enumeration::<3>(seed + 100);
+        }
+
+        let mut rng = Rng::new(42);
+        for _ in 0..20 {
+            let graph: Graph<u32, 2> = random_graph(&mut rng, 8, 24);
+            let mut all = Vec::new();
+            enumerate(&graph, 0, 7, &mut vec![0], [0; 2], &mut all);
+            for budget in [0, 5, 10, 20] {
+                let expected = all.iter().filter(|costs| costs[1] <= budget).min().copied();
+                assert_eq!(constrained_shortest_path(&graph, &0, &7, budget).map(|path| path.costs), expected);
+            }
+        }
+    }
+}
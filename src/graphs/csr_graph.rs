@@ -0,0 +1,430 @@
+/**
+ * Compressed Sparse Row (CSR) Graph
+ *
+ * An immutable graph representation for large, read-only graphs.
+ *
+ * Every node is given a dense `u32` index. The outgoing edges of all nodes are stored back to back
+ * in a single `targets` array (with a parallel `weights` array) and `offsets[u]..offsets[u + 1]`
+ * is the slice of edges leaving node `u`. Compared to the nested `HashMap` graphs used elsewhere in
+ * this module there is no per-node allocation and no hashing during a traversal, so the memory
+ * footprint is small and neighbor scans walk contiguous memory.
+ *
+ * The external IDs (for example `char` or `String`) are kept in a lookup table so results can be
+ * mapped back with `id` and `to_ids`.
+ *
+ * Build: O(V + E)
+ * Space Complexity: O(V + E)
+ */
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// Marks "no predecessor" in the predecessor arrays, similar to the '\0' used by the HashMap graphs.
+const NONE: u32 = u32::MAX;
+
+pub struct CsrGraph<N> {
+    offsets: Vec<usize>,
+    targets: Vec<u32>,
+    weights: Vec<u32>,
+    ids: Vec<N>,
+    index: HashMap<N, u32>,
+}
+
+impl<N: Hash + Eq + Clone> CsrGraph<N> {
+    /**
+     * Build a graph from a list of directed, weighted edges `(from, to, weight)`.
+     *
+     * Nodes are numbered in the order they first appear. The edges of each node keep their input order.
+     */
+    pub fn from_edges<I>(edges: I) -> Self
+    where
+        I: IntoIterator<Item = (N, N, u32)>,
+    {
+        Self::build(Vec::new(), HashMap::new(), edges)
+    }
+
+    /**
+     * Build a graph from the unweighted adjacency lists used by `bfs_graph` and `dfs_graph`.
+     *
+     * Every edge gets a weight of 1.
+     */
+    pub fn from_adjacency(graph: &HashMap<N, Vec<N>>) -> Self {
+        let (ids, index) = Self::register_keys(graph.keys());
+        let edges = graph.iter().flat_map(|(from, neighbors)| {
+            neighbors.iter().map(move |to| (from.clone(), to.clone(), 1))
+        });
+        Self::build(ids, index, edges)
+    }
+
+    /**
+     * Build a graph from the weighted adjacency maps used by the Dijkstra modules.
+     */
+    pub fn from_weighted(graph: &HashMap<N, HashMap<N, u32>>) -> Self {
+        let (ids, index) = Self::register_keys(graph.keys());
+        let edges = graph.iter().flat_map(|(from, neighbors)| {
+            neighbors.iter().map(move |(to, &weight)| (from.clone(), to.clone(), weight))
+        });
+        Self::build(ids, index, edges)
+    }
+
+    // Assign indices to the keys of an adjacency map so nodes without edges are kept.
+    fn register_keys<'a, K>(keys: K) -> (Vec<N>, HashMap<N, u32>)
+    where
+        K: Iterator<Item = &'a N>,
+        N: 'a,
+    {
+        let mut ids = Vec::new();
+        let mut index = HashMap::new();
+        for key in keys {
+            index.insert(key.clone(), ids.len() as u32);
+            ids.push(key.clone());
+        }
+        (ids, index)
+    }
+
+    fn build<I>(mut ids: Vec<N>, mut index: HashMap<N, u32>, edges: I) -> Self
+    where
+        I: IntoIterator<Item = (N, N, u32)>,
+    {
+        let mut intern = |id: N| -> u32 {
+            *index.entry(id.clone()).or_insert_with(|| {
+                ids.push(id);
+                (ids.len() - 1) as u32
+            })
+        };
+
+        let mut sources = Vec::new();
+        let mut unsorted = Vec::new();
+        for (from, to, weight) in edges {
+            sources.push(intern(from));
+            unsorted.push((intern(to), weight));
+        }
+        assert!(ids.len() < NONE as usize, "CsrGraph supports at most u32::MAX - 1 nodes");
+
+        // Counting sort of the edges by their source node.
+        let mut offsets = vec![0; ids.len() + 1];
+        for &source in &sources {
+            offsets[source as usize + 1] += 1;
+        }
+        for i in 1..offsets.len() {
+            offsets[i] += offsets[i - 1];
+        }
+
+        let mut next = offsets.clone();
+        let mut targets = vec![0; unsorted.len()];
+        let mut weights = vec![0; unsorted.len()];
+        for (&source, &(target, weight)) in sources.iter().zip(&unsorted) {
+            let slot = next[source as usize];
+            targets[slot] = target;
+            weights[slot] = weight;
+            next[source as usize] += 1;
+        }
+
+        CsrGraph { offsets, targets, weights, ids, index }
+    }
+
+    /**
+     * Get the dense index of an external ID.
+     */
+    pub fn index_of(&self, id: &N) -> Option<u32> {
+        self.index.get(id).copied()
+    }
+
+    /**
+     * Get the external ID of a dense index.
+     */
+    pub fn id(&self, node: u32) -> Option<&N> {
+        self.ids.get(node as usize)
+    }
+
+    /**
+     * Map a path of dense indices back to external IDs.
+     */
+    pub fn to_ids(&self, path: &[u32]) -> Vec<N> {
+        path.iter().map(|&node| self.ids[node as usize].clone()).collect()
+    }
+
+    /**
+     * The same graph with every edge reversed. Nodes keep their indices, so `neighbors` of the
+     * transpose are the incoming edges of the original.
+     *
+     * Time Complexity: O(V + E)
+     */
+    pub fn transpose(&self) -> Self {
+        let edges = (0..self.node_count() as u32).flat_map(|from| {
+            self.edges(from)
+                .map(move |(to, weight)| (self.ids[to as usize].clone(), self.ids[from as usize].clone(), weight))
+        });
+        Self::build(self.ids.clone(), self.index.clone(), edges)
+    }
+}
+
+impl<N> CsrGraph<N> {
+    pub fn node_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /**
+     * The targets of the edges leaving `node`.
+     */
+    pub fn neighbors(&self, node: u32) -> &[u32] {
+        let node = node as usize;
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /**
+     * The `(target, weight)` pairs of the edges leaving `node`.
+     */
+    pub fn edges(&self, node: u32) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let range = self.offsets[node as usize]..self.offsets[node as usize + 1];
+        self.targets[range.clone()].iter().copied().zip(self.weights[range].iter().copied())
+    }
+
+    /**
+     * Breadth First Search
+     *
+     * Returns the shortest (fewest edges) path from start to needle, if it exists.
+     *
+     * Time Complexity: O(V + E)
+     * Space Complexity: O(V)
+     */
+    pub fn breadth_first_search(&self, start: u32, needle: u32) -> Option<Vec<u32>> {
+        let mut visited = vec![false; self.node_count()];
+        let mut predecessors = vec![NONE; self.node_count()];
+        let mut queue = VecDeque::new();
+
+        queue.push_back(start);
+        visited[start as usize] = true;
+
+        while let Some(current) = queue.pop_front() {
+            if current == needle {
+                return Some(reconstruct_path(&predecessors, needle));
+            }
+
+            for &neighbor in self.neighbors(current) {
+                if !visited[neighbor as usize] {
+                    queue.push_back(neighbor);
+                    visited[neighbor as usize] = true;
+                    predecessors[neighbor as usize] = current;
+                }
+            }
+        }
+
+        None
+    }
+
+    /**
+     * Number of edges on the shortest path from source to every node, `None` if unreachable.
+     *
+     * Time Complexity: O(V + E)
+     * Space Complexity: O(V)
+     */
+    pub fn bfs_distances(&self, source: u32) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.node_count()];
+        let mut queue = VecDeque::new();
+
+        distances[source as usize] = Some(0);
+        queue.push_back(source);
+
+        while let Some(current) = queue.pop_front() {
+            let next = distances[current as usize].map(|d| d + 1);
+            for &neighbor in self.neighbors(current) {
+                if distances[neighbor as usize].is_none() {
+                    distances[neighbor as usize] = next;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /**
+     * Depth First Search
+     *
+     * Returns the first path from start to needle found by a depth first traversal, if it exists.
+     *
+     * Neighbors are explored in the same order as the recursive version in `dfs_graph`, but an
+     * explicit stack is used so that very deep graphs do not overflow the call stack.
+     *
+     * Time Complexity: O(V + E)
+     * Space Complexity: O(V)
+     */
+    pub fn depth_first_search(&self, start: u32, needle: u32) -> Option<Vec<u32>> {
+        let mut visited = vec![false; self.node_count()];
+        let mut predecessors = vec![NONE; self.node_count()];
+        // Each frame is a node and the position of the next edge to try.
+        let mut stack = vec![(start, self.offsets[start as usize])];
+        visited[start as usize] = true;
+
+        while let Some(frame) = stack.last_mut() {
+            let (current, edge) = *frame;
+            if current == needle {
+                return Some(reconstruct_path(&predecessors, needle));
+            }
+
+            if edge == self.offsets[current as usize + 1] {
+                stack.pop();
+                continue;
+            }
+
+            let neighbor = self.targets[edge];
+            frame.1 += 1;
+            if !visited[neighbor as usize] {
+                visited[neighbor as usize] = true;
+                predecessors[neighbor as usize] = current;
+                stack.push((neighbor, self.offsets[neighbor as usize]));
+            }
+        }
+
+        None
+    }
+
+    /**
+     * Dijkstra's Shortest Path Algorithm using a binary heap.
+     *
+     * Returns the lowest weight path from start to needle, if it exists.
+     *
+     * Time Complexity: O((V + E) log V)
+     * Space Complexity: O(V)
+     */
+    pub fn dijkstras_shortest_path(&self, start: u32, needle: u32) -> Option<Vec<u32>> {
+        let (distances, predecessors) = self.dijkstra(start, Some(needle));
+        distances[needle as usize].map(|_| reconstruct_path(&predecessors, needle))
+    }
+
+    /**
+     * Lowest total weight from source to every node, `None` if unreachable.
+     *
+     * Time Complexity: O((V + E) log V)
+     * Space Complexity: O(V)
+     */
+    pub fn dijkstra_distances(&self, source: u32) -> Vec<Option<u64>> {
+        self.dijkstra(source, None).0
+    }
+
+    fn dijkstra(&self, source: u32, needle: Option<u32>) -> (Vec<Option<u64>>, Vec<u32>) {
+        let mut distances = vec![None; self.node_count()];
+        let mut predecessors = vec![NONE; self.node_count()];
+        let mut settled = vec![false; self.node_count()];
+        let mut heap = BinaryHeap::new();
+
+        distances[source as usize] = Some(0);
+        heap.push(Reverse((0u64, source)));
+
+        while let Some(Reverse((cost, position))) = heap.pop() {
+            // Skip stale entries for nodes that were already settled with a lower cost
+            if settled[position as usize] {
+                continue;
+            }
+            settled[position as usize] = true;
+
+            if Some(position) == needle {
+                break;
+            }
+
+            for (neighbor, weight) in self.edges(position) {
+                let next = cost + weight as u64;
+                let known = distances[neighbor as usize];
+                if known.is_none_or(|known| next < known) {
+                    distances[neighbor as usize] = Some(next);
+                    predecessors[neighbor as usize] = position;
+                    heap.push(Reverse((next, neighbor)));
+                }
+            }
+        }
+
+        (distances, predecessors)
+    }
+}
+
+fn reconstruct_path(predecessors: &[u32], needle: u32) -> Vec<u32> {
+    let mut path = vec![needle];
+    let mut prev = predecessors[needle as usize];
+    while prev != NONE {
+        path.push(prev);
+        prev = predecessors[prev as usize];
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_graph() -> HashMap<char, Vec<char>> {
+        let mut graph = HashMap::new();
+        graph.insert('A', vec!['B', 'C']);
+        graph.insert('B', vec!['D']);
+        graph.insert('C', vec![]);
+        graph.insert('D', vec!['E', 'F']);
+        graph.insert('E', vec!['G']);
+        graph.insert('F', vec!['C']);
+        graph.insert('G', vec![]);
+        graph.insert('H', vec![]);
+        graph
+    }
+
+    #[test]
+    fn test_csr_layout() {
+        let graph = CsrGraph::from_edges(vec![("x", "y", 4), ("y", "z", 2), ("x", "z", 7)]);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+
+        let x = graph.index_of(&"x").unwrap();
+        let z = graph.index_of(&"z").unwrap();
+        assert_eq!(graph.id(x), Some(&"x"));
+        assert_eq!(graph.to_ids(graph.neighbors(x)), vec!["y", "z"]);
+        assert_eq!(graph.edges(x).map(|(_, w)| w).collect::<Vec<_>>(), vec![4, 7]);
+        assert!(graph.neighbors(z).is_empty());
+        assert_eq!(graph.index_of(&"w"), None);
+
+        let transpose = graph.transpose();
+        assert_eq!(transpose.index_of(&"z"), Some(z));
+        assert_eq!(transpose.to_ids(transpose.neighbors(z)), vec!["x", "y"]);
+        assert!(transpose.neighbors(x).is_empty());
+    }
+
+    #[test]
+    fn test_breadth_and_depth_first_search() {
+        let graph = CsrGraph::from_adjacency(&create_test_graph());
+        let a = graph.index_of(&'A').unwrap();
+        let g = graph.index_of(&'G').unwrap();
+        let h = graph.index_of(&'H').unwrap();
+
+        let path = graph.breadth_first_search(a, g).unwrap();
+        assert_eq!(graph.to_ids(&path), vec!['A', 'B', 'D', 'E', 'G']);
+        let path = graph.depth_first_search(a, g).unwrap();
+        assert_eq!(graph.to_ids(&path), vec!['A', 'B', 'D', 'E', 'G']);
+        assert_eq!(graph.depth_first_search(a, a), Some(vec![a]));
+
+        assert_eq!(graph.breadth_first_search(a, h), None);
+        assert_eq!(graph.depth_first_search(a, h), None);
+        assert_eq!(graph.bfs_distances(a)[g as usize], Some(4));
+        assert_eq!(graph.bfs_distances(a)[h as usize], None);
+    }
+
+    #[test]
+    fn test_dijkstras_shortest_path() {
+        let mut weighted = HashMap::new();
+        weighted.insert('A', HashMap::from([('B', 3), ('C', 6), ('D', 1)]));
+        weighted.insert('B', HashMap::from([('C', 2), ('E', 7)]));
+        weighted.insert('C', HashMap::from([('F', 2)]));
+        weighted.insert('D', HashMap::from([('B', 1), ('E', 3)]));
+        weighted.insert('E', HashMap::from([('F', 8)]));
+        weighted.insert('F', HashMap::new());
+        let graph = CsrGraph::from_weighted(&weighted);
+        let a = graph.index_of(&'A').unwrap();
+        let f = graph.index_of(&'F').unwrap();
+
+        let path = graph.dijkstras_shortest_path(a, f).unwrap();
+        assert_eq!(graph.to_ids(&path), vec!['A', 'D', 'B', 'C', 'F']);
+        assert_eq!(graph.dijkstra_distances(a)[f as usize], Some(6));
+        assert_eq!(graph.dijkstras_shortest_path(f, a), None);
+    }
+}
@@ -0,0 +1,460 @@
+/**
+ * Cycle Detection and Enumeration
+ *
+ * - `has_cycle` / `find_cycle` detect a cycle in a directed graph with a depth first search that
+ *   colors nodes white (unvisited), gray (on the current path) and black (finished). An edge to a
+ *   gray node is a back edge and closes a cycle with the nodes on the path.
+ * - `has_cycle_undirected` / `find_cycle_undirected` do the same for undirected graphs, where any
+ *   edge to a visited node other than the parent closes a cycle.
+ * - `elementary_cycles` / `elementary_cycles_undirected` lazily list every elementary cycle (no
+ *   repeated nodes) with Johnson's algorithm, optionally only up to a maximum length.
+ *
+ * Cycles are returned as the list of nodes along the cycle, without repeating the first node at
+ * the end: `['A', 'B', 'C']` means A -> B -> C -> A. A self loop is the cycle `['A']`.
+ *
+ * Nodes are visited in sorted order, so the results do not depend on `HashMap` iteration order.
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+type Graph<N> = HashMap<N, Vec<N>>;
+
+const WHITE: u8 = 0;
+const GRAY: u8 = 1;
+const BLACK: u8 = 2;
+
+// The graph with its nodes numbered in sorted order.
+struct Indexed<N> {
+    nodes: Vec<N>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl<N: Hash + Eq + Clone + Ord> Indexed<N> {
+    fn new(graph: &Graph<N>, undirected: bool) -> Self {
+        let mut nodes: Vec<N> = graph.keys().chain(graph.values().flatten()).cloned().collect();
+        nodes.sort();
+        nodes.dedup();
+        let index: HashMap<&N, usize> = nodes.iter().enumerate().map(|(i, node)| (node, i)).collect();
+
+        let mut adjacency = vec![Vec::new(); nodes.len()];
+        for (from, neighbors) in graph {
+            for to in neighbors {
+                adjacency[index[from]].push(index[to]);
+                if undirected {
+                    adjacency[index[to]].push(index[from]);
+                }
+            }
+        }
+        for neighbors in adjacency.iter_mut() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+
+        Indexed { nodes, adjacency }
+    }
+
+    fn to_ids(&self, cycle: &[usize]) -> Vec<N> {
+        cycle.iter().map(|&node| self.nodes[node].clone()).collect()
+    }
+}
+
+/**
+ * Check whether a directed graph contains a cycle.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V + E)
+ */
+pub fn has_cycle<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> bool {
+    find_cycle(graph).is_some()
+}
+
+/**
+ * Find one cycle in a directed graph, for example the dependency loop that makes a configuration invalid.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V + E)
+ *
+ * @param graph A directed graph
+ * @return The nodes of a cycle in edge order, if there is one
+ */
+pub fn find_cycle<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> Option<Vec<N>> {
+    let indexed = Indexed::new(graph, false);
+    let n = indexed.nodes.len();
+    let mut color = vec![WHITE; n];
+    // Position of each gray node on the stack, to cut the cycle out of the current path
+    let mut position = vec![0; n];
+
+    for root in 0..n {
+        if color[root] != WHITE {
+            continue;
+        }
+        let mut stack = vec![(root, 0)];
+        color[root] = GRAY;
+
+        while let Some(frame) = stack.last_mut() {
+            let (node, edge) = *frame;
+            let Some(&neighbor) = indexed.adjacency[node].get(edge) else {
+                color[node] = BLACK;
+                stack.pop();
+                continue;
+            };
+            frame.1 += 1;
+
+            match color[neighbor] {
+                WHITE => {
+                    color[neighbor] = GRAY;
+                    position[neighbor] = stack.len();
+                    stack.push((neighbor, 0));
+                }
+                GRAY => {
+                    let cycle: Vec<usize> = stack[position[neighbor]..].iter().map(|&(node, _)| node).collect();
+                    return Some(indexed.to_ids(&cycle));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * Check whether an undirected graph contains a cycle.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V + E)
+ */
+pub fn has_cycle_undirected<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> bool {
+    find_cycle_undirected(graph).is_some()
+}
+
+/**
+ * Find one cycle in an undirected graph.
+ *
+ * Edges may be listed in one or both directions. Going back and forth over the same edge is not a cycle.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V + E)
+ *
+ * @param graph An undirected graph
+ * @return The nodes of a cycle in edge order, if there is one
+ */
+pub fn find_cycle_undirected<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> Option<Vec<N>> {
+    let indexed = Indexed::new(graph, true);
+    let n = indexed.nodes.len();
+    let mut color = vec![WHITE; n];
+    let mut position = vec![0; n];
+
+    for root in 0..n {
+        if color[root] != WHITE {
+            continue;
+        }
+        // Each frame is a node, the next edge to try and the parent it was reached from
+        let mut stack = vec![(root, 0, usize::MAX)];
+        color[root] = GRAY;
+
+        while let Some(frame) = stack.last_mut() {
+            let (node, edge, parent) = *frame;
+            let Some(&neighbor) = indexed.adjacency[node].get(edge) else {
+                color[node] = BLACK;
+                stack.pop();
+                continue;
+            };
+            frame.1 += 1;
+
+            if neighbor == parent {
+                continue;
+            }
+            match color[neighbor] {
+                WHITE => {
+                    color[neighbor] = GRAY;
+                    position[neighbor] = stack.len();
+                    stack.push((neighbor, 0, node));
+                }
+                GRAY => {
+                    let cycle: Vec<usize> = stack[position[neighbor]..].iter().map(|&(node, _, _)| node).collect();
+                    return Some(indexed.to_ids(&cycle));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * List every elementary cycle of a directed graph with Johnson's algorithm.
+ *
+ * The cycles are produced lazily, so taking only the first few is cheap even when the graph has
+ * exponentially many. Each cycle starts at its smallest node.
+ *
+ * Time Complexity: O((V + E)(C + 1)) for C cycles
+ * Space Complexity: O(V + E)
+ *
+ * @param graph A directed graph
+ * @param max_length Only list cycles with at most this many nodes
+ * @return An iterator over the cycles
+ */
+pub fn elementary_cycles<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>, max_length: Option<usize>) -> ElementaryCycles<N> {
+    ElementaryCycles::new(Indexed::new(graph, false), max_length, false)
+}
+
+/**
+ * List every elementary cycle of an undirected graph.
+ *
+ * Runs Johnson's algorithm on the graph with every edge in both directions, skipping the trivial
+ * back and forth cycles over a single edge and reporting every other cycle in only one of its two
+ * directions.
+ *
+ * @param graph An undirected graph, with edges listed in one or both directions
+ * @param max_length Only list cycles with at most this many nodes
+ * @return An iterator over the cycles
+ */
+pub fn elementary_cycles_undirected<N: Hash + Eq + Clone + Ord>(
+    graph: &Graph<N>,
+    max_length: Option<usize>,
+) -> ElementaryCycles<N> {
+    ElementaryCycles::new(Indexed::new(graph, true), max_length, true)
+}
+
+// Nodes reachable from `s` using only nodes >= s.
+fn reach(adjacency: &[Vec<usize>], s: usize) -> Vec<bool> {
+    let mut seen = vec![false; adjacency.len()];
+    let mut stack = vec![s];
+    seen[s] = true;
+    while let Some(node) = stack.pop() {
+        for &next in &adjacency[node] {
+            if next >= s && !seen[next] {
+                seen[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+    seen
+}
+
+struct Frame {
+    node: usize,
+    next_edge: usize,
+    // Whether a cycle (or a path cut short by `max_length`) was found below this node
+    found: bool,
+}
+
+/**
+ * Iterator over elementary cycles, see `elementary_cycles`.
+ *
+ * Johnson's algorithm takes every node `s` in order and searches for paths back to `s` inside the
+ * strongly connected component of `s` among the nodes `>= s`. A node that cannot currently lead
+ * back to `s` stays blocked until one of its successors is unblocked, which is what keeps dead ends
+ * from being explored over and over.
+ *
+ * The recursive search is kept on an explicit stack so the iterator can stop after every cycle.
+ */
+pub struct ElementaryCycles<N> {
+    graph: Indexed<N>,
+    reverse: Vec<Vec<usize>>,
+    max_length: Option<usize>,
+    undirected: bool,
+    start: usize,
+    in_component: Vec<bool>,
+    blocked: Vec<bool>,
+    blocked_by: Vec<Vec<usize>>,
+    stack: Vec<Frame>,
+}
+
+impl<N: Hash + Eq + Clone + Ord> ElementaryCycles<N> {
+    fn new(graph: Indexed<N>, max_length: Option<usize>, undirected: bool) -> Self {
+        let n = graph.nodes.len();
+        let mut reverse = vec![Vec::new(); n];
+        for (from, neighbors) in graph.adjacency.iter().enumerate() {
+            for &to in neighbors {
+                reverse[to].push(from);
+            }
+        }
+        ElementaryCycles {
+            graph,
+            reverse,
+            max_length,
+            undirected,
+            start: 0,
+            in_component: vec![false; n],
+            blocked: vec![false; n],
+            blocked_by: vec![Vec::new(); n],
+            stack: Vec::new(),
+        }
+    }
+
+    // Mark the strongly connected component of `s` among the nodes >= s: reachable from `s` and able to reach `s`.
+    fn prepare(&mut self, s: usize) {
+        let forward = reach(&self.graph.adjacency, s);
+        let backward = reach(&self.reverse, s);
+        for node in 0..self.in_component.len() {
+            self.in_component[node] = node >= s && forward[node] && backward[node];
+            self.blocked[node] = false;
+            self.blocked_by[node].clear();
+        }
+    }
+
+    fn unblock(&mut self, node: usize) {
+        let mut pending = vec![node];
+        while let Some(node) = pending.pop() {
+            if self.blocked[node] {
+                self.blocked[node] = false;
+                pending.append(&mut self.blocked_by[node]);
+            }
+        }
+    }
+
+    // Undirected graphs report each cycle once, skipping the back and forth over a single edge.
+    fn accept(&self, cycle: &[usize]) -> bool {
+        if !self.undirected {
+            return true;
+        }
+        match cycle.len() {
+            1 => true,
+            2 => false,
+            len => cycle[1] < cycle[len - 1],
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone + Ord> Iterator for ElementaryCycles<N> {
+    type Item = Vec<N>;
+
+    fn next(&mut self) -> Option<Vec<N>> {
+        loop {
+            if self.stack.is_empty() {
+                if self.start >= self.graph.nodes.len() {
+                    return None;
+                }
+                let s = self.start;
+                self.start += 1;
+                self.prepare(s);
+                self.blocked[s] = true;
+                self.stack.push(Frame { node: s, next_edge: 0, found: false });
+            }
+
+            let s = self.start - 1;
+            let depth = self.stack.len();
+            let frame = self.stack.last_mut().unwrap();
+            let node = frame.node;
+
+            if let Some(&next) = self.graph.adjacency[node].get(frame.next_edge) {
+                frame.next_edge += 1;
+                if !self.in_component[next] {
+                    continue;
+                }
+                if next == s {
+                    frame.found = true;
+                    let cycle: Vec<usize> = self.stack.iter().map(|frame| frame.node).collect();
+                    if self.accept(&cycle) {
+                        return Some(self.graph.to_ids(&cycle));
+                    }
+                } else if !self.blocked[next] {
+                    if self.max_length.is_some_and(|max| depth >= max) {
+                        // The path was cut short, so there may still be a way back to `s` through `next`.
+                        // Treat it as found so the node is unblocked and can be tried again on a shorter path.
+                        frame.found = true;
+                    } else {
+                        self.blocked[next] = true;
+                        self.stack.push(Frame { node: next, next_edge: 0, found: false });
+                    }
+                }
+                continue;
+            }
+
+            // Every edge of `node` has been tried
+            let finished = self.stack.pop().unwrap();
+            if finished.found {
+                self.unblock(node);
+            } else {
+                for i in 0..self.graph.adjacency[node].len() {
+                    let next = self.graph.adjacency[node][i];
+                    if self.in_component[next] && !self.blocked_by[next].contains(&node) {
+                        self.blocked_by[next].push(node);
+                    }
+                }
+            }
+            if let Some(parent) = self.stack.last_mut() {
+                parent.found |= finished.found;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete_graph(n: u8) -> Graph<char> {
+        let nodes: Vec<char> = (0..n).map(|i| (b'A' + i) as char).collect();
+        nodes.iter().map(|&from| (from, nodes.iter().copied().filter(|&to| to != from).collect())).collect()
+    }
+
+    fn is_cycle(graph: &Graph<char>, cycle: &[char], undirected: bool) -> bool {
+        let has_edge = |a: char, b: char| {
+            graph.get(&a).is_some_and(|n| n.contains(&b)) || (undirected && graph.get(&b).is_some_and(|n| n.contains(&a)))
+        };
+        (0..cycle.len()).all(|i| has_edge(cycle[i], cycle[(i + 1) % cycle.len()]))
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let mut graph = Graph::new();
+        graph.insert('A', vec!['B']);
+        graph.insert('B', vec!['C', 'D']);
+        graph.insert('C', vec!['E']);
+        graph.insert('D', vec!['E']);
+        graph.insert('E', vec![]);
+        assert!(!has_cycle(&graph));
+        assert_eq!(find_cycle(&graph), None);
+        // Without directions A-B-C-E-D-B is a cycle
+        assert!(has_cycle_undirected(&graph));
+        let cycle = find_cycle_undirected(&graph).unwrap();
+        assert_eq!(cycle.len(), 4);
+        assert!(is_cycle(&graph, &cycle, true));
+
+        graph.insert('E', vec!['A']);
+        assert!(has_cycle(&graph));
+        let cycle = find_cycle(&graph).unwrap();
+        assert_eq!(cycle, vec!['A', 'B', 'C', 'E']);
+
+        let mut tree = Graph::new();
+        tree.insert('A', vec!['B', 'C']);
+        tree.insert('B', vec!['A', 'D']);
+        assert!(!has_cycle_undirected(&tree));
+        tree.insert('D', vec!['D']);
+        assert_eq!(find_cycle_undirected(&tree), Some(vec!['D']));
+    }
+
+    #[test]
+    fn test_elementary_cycles() {
+        // A complete directed graph on 4 nodes has 6 two-cycles, 8 three-cycles and 6 four-cycles
+        let graph = complete_graph(4);
+        let cycles: Vec<Vec<char>> = elementary_cycles(&graph, None).collect();
+        assert_eq!(cycles.len(), 20);
+        assert!(cycles.iter().all(|cycle| is_cycle(&graph, cycle, false)));
+        let mut unique = cycles.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 20);
+
+        assert_eq!(elementary_cycles(&graph, Some(3)).count(), 14);
+        assert_eq!(elementary_cycles(&graph, Some(2)).count(), 6);
+        assert_eq!(elementary_cycles(&graph, Some(2)).next(), Some(vec!['A', 'B']));
+
+        // Undirected: 4 triangles and 3 four-cycles
+        assert_eq!(elementary_cycles_undirected(&graph, None).count(), 7);
+        assert_eq!(elementary_cycles_undirected(&graph, Some(3)).count(), 4);
+    }
+
+    #[test]
+    fn test_elementary_cycles_is_lazy() {
+        // A complete directed graph on 12 nodes has far too many cycles to list
+        let graph = complete_graph(12);
+        let first: Vec<Vec<char>> = elementary_cycles(&graph, None).take(5).collect();
+        assert_eq!(first.len(), 5);
+        assert!(first.iter().all(|cycle| cycle[0] == 'A'));
+    }
+}
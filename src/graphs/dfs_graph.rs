@@ -56,7 +56,7 @@ fn depth_first_search_recursive(
     None
 }
  
-fn depth_first_search(graph: &Graph, start: char, needle: char) -> Option<Vec<char>> {
+pub fn depth_first_search(graph: &Graph, start: char, needle: char) -> Option<Vec<char>> {
     let mut visited = HashSet::new();
     let mut predecessors = HashMap::new();
     visited.insert(start);
@@ -70,6 +70,24 @@ fn depth_first_search(graph: &Graph, start: char, needle: char) -> Option<Vec<ch
         needle,
     )
 }   
+
+/**
+ * Depth First Search Tree
+ *
+ * Searches the whole graph from start and returns the `predecessors` map instead of a single path.
+ * It maps every reachable node to the node it was discovered from, and start to '\0'.
+ * `trees::lowest_common_ancestor` can answer queries on the tree.
+ */
+pub fn dfs_tree(graph: &Graph, start: char) -> HashMap<char, char> {
+    let mut visited = HashSet::new();
+    let mut predecessors = HashMap::new();
+    visited.insert(start);
+    predecessors.insert(start, '\0');
+
+    // '\0' is never a node, so the search runs until every reachable node is visited
+    depth_first_search_recursive(graph, &mut visited, &mut predecessors, start, '\0');
+    predecessors
+}
  
 #[cfg(test)]
 mod tests {
@@ -122,4 +140,21 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_dfs_tree() {
+        let mut graph = HashMap::new();
+        graph.insert('A', vec!['B', 'C']);
+        graph.insert('B', vec!['D']);
+        graph.insert('C', vec!['D', 'E']);
+        graph.insert('D', vec!['E']);
+        graph.insert('F', vec!['A']);
+
+        let tree = dfs_tree(&graph, 'A');
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree[&'A'], '\0');
+        assert_eq!(tree[&'D'], 'B');
+        assert_eq!(tree[&'E'], 'D');
+        assert_eq!(tree[&'C'], 'A');
+    }
 }
\ No newline at end of file
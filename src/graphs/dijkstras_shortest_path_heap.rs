@@ -41,7 +41,7 @@ impl PartialOrd for State {
     }
 }
 
-fn dijkstras_shortest_path(graph: &Graph, start: char, needle: char) -> Option<Vec<char>> {
+pub fn dijkstras_shortest_path(graph: &Graph, start: char, needle: char) -> Option<Vec<char>> {
     let mut heap = BinaryHeap::new();
     let mut distances = HashMap::new();
     let mut predecessors = HashMap::new();
@@ -0,0 +1,315 @@
+/**
+ * Dynamic Single Source Shortest Paths
+ *
+ * Keeps a shortest path tree from one source up to date while edges are inserted, removed or
+ * reweighted, repairing only the part of the tree an update affects (in the style of
+ * Ramalingam and Reps):
+ *
+ * - When an edge `(u, v)` gets cheaper (or is inserted) and now improves `v`, the improvement is
+ *   pushed outwards from `v` with Dijkstra's algorithm. Nodes whose distance does not improve are
+ *   never touched.
+ * - When a tree edge `(u, v)` gets more expensive (or is removed), only the subtree below `v` can get
+ *   longer paths. Those nodes are reset, seeded with their best edge from outside the subtree, and
+ *   settled again with Dijkstra's algorithm. A change to an edge outside the tree changes nothing.
+ *
+ * Every update returns the number of nodes it had to settle, which is usually a small fraction of
+ * the graph.
+ *
+ * Time Complexity: O(A log A) per update, where A is the number of affected nodes and their edges
+ * Space Complexity: O(V + E)
+ */
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+const UNREACHABLE: u64 = u64::MAX;
+
+pub struct DynamicShortestPaths<N> {
+    ids: Vec<N>,
+    index: HashMap<N, usize>,
+    outgoing: Vec<HashMap<usize, u32>>,
+    incoming: Vec<HashMap<usize, u32>>,
+    source: usize,
+    distances: Vec<u64>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<N: Hash + Eq + Clone> DynamicShortestPaths<N> {
+    /**
+     * Build the shortest path tree from `source` on one of the weighted graphs used by
+     * `dijkstras_shortest_path_heap`.
+     */
+    pub fn new(graph: &HashMap<N, HashMap<N, u32>>, source: N) -> Self {
+        let mut paths = DynamicShortestPaths {
+            ids: Vec::new(),
+            index: HashMap::new(),
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+            source: 0,
+            distances: Vec::new(),
+            parents: Vec::new(),
+        };
+        paths.source = paths.add_node(source);
+        for (from, neighbors) in graph {
+            let from = paths.add_node(from.clone());
+            for (to, &weight) in neighbors {
+                let to = paths.add_node(to.clone());
+                paths.outgoing[from].insert(to, weight);
+                paths.incoming[to].insert(from, weight);
+            }
+        }
+
+        let source = paths.source;
+        paths.distances[source] = 0;
+        paths.settle(BinaryHeap::from([Reverse((0, source))]));
+        paths
+    }
+
+    pub fn source(&self) -> &N {
+        &self.ids[self.source]
+    }
+
+    /**
+     * Length of the shortest path from the source to `node`, `None` if there is no path.
+     */
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        let node = *self.index.get(node)?;
+        Some(self.distances[node]).filter(|&distance| distance != UNREACHABLE)
+    }
+
+    /**
+     * The shortest path from the source to `node`, both included.
+     */
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        let mut current = *self.index.get(node)?;
+        if self.distances[current] == UNREACHABLE {
+            return None;
+        }
+        let mut path = vec![self.ids[current].clone()];
+        while let Some(parent) = self.parents[current] {
+            path.push(self.ids[parent].clone());
+            current = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /**
+     * The node before `node` on its shortest path, `None` for the source and unreachable nodes.
+     */
+    pub fn parent(&self, node: &N) -> Option<&N> {
+        let parent = self.parents[*self.index.get(node)?]?;
+        Some(&self.ids[parent])
+    }
+
+    pub fn weight(&self, from: &N, to: &N) -> Option<u32> {
+        let (from, to) = (self.index.get(from)?, self.index.get(to)?);
+        self.outgoing[*from].get(to).copied()
+    }
+
+    /**
+     * Insert the edge `from -> to`, or change its weight if it already exists. Unknown nodes are added.
+     *
+     * @return The number of nodes that were settled again
+     */
+    pub fn set_edge(&mut self, from: N, to: N, weight: u32) -> usize {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        let previous = self.outgoing[from].insert(to, weight);
+        self.incoming[to].insert(from, weight);
+
+        match previous {
+            Some(previous) if weight > previous => self.increase(from, to),
+            Some(previous) if weight == previous => 0,
+            _ => self.decrease(from, to, weight),
+        }
+    }
+
+    /**
+     * Remove the edge `from -> to`. Removing an edge that does not exist does nothing.
+     *
+     * @return The number of nodes that were settled again
+     */
+    pub fn remove_edge(&mut self, from: &N, to: &N) -> usize {
+        let (from, to) = match (self.index.get(from), self.index.get(to)) {
+            (Some(&from), Some(&to)) => (from, to),
+            _ => return 0,
+        };
+        if self.outgoing[from].remove(&to).is_none() {
+            return 0;
+        }
+        self.incoming[to].remove(&from);
+        self.increase(from, to)
+    }
+
+    fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.index.get(&node) {
+            return index;
+        }
+        let index = self.ids.len();
+        self.ids.push(node.clone());
+        self.index.insert(node, index);
+        self.outgoing.push(HashMap::new());
+        self.incoming.push(HashMap::new());
+        self.distances.push(UNREACHABLE);
+        self.parents.push(None);
+        index
+    }
+
+    // The edge `from -> to` got cheaper: only `to` and the nodes it improves can change.
+    fn decrease(&mut self, from: usize, to: usize, weight: u32) -> usize {
+        if self.distances[from] == UNREACHABLE {
+            return 0;
+        }
+        let candidate = self.distances[from] + weight as u64;
+        if candidate >= self.distances[to] {
+            return 0;
+        }
+        self.distances[to] = candidate;
+        self.parents[to] = Some(from);
+        self.settle(BinaryHeap::from([Reverse((candidate, to))]))
+    }
+
+    // The edge `from -> to` got more expensive or was removed: only the subtree of `to` can change.
+    fn increase(&mut self, from: usize, to: usize) -> usize {
+        if self.parents[to] != Some(from) {
+            return 0;
+        }
+
+        let mut affected = vec![to];
+        let mut in_subtree = vec![false; self.ids.len()];
+        in_subtree[to] = true;
+        let mut i = 0;
+        while i < affected.len() {
+            let node = affected[i];
+            for &child in self.outgoing[node].keys() {
+                if self.parents[child] == Some(node) && !in_subtree[child] {
+                    in_subtree[child] = true;
+                    affected.push(child);
+                }
+            }
+            i += 1;
+        }
+        for &node in &affected {
+            self.distances[node] = UNREACHABLE;
+            self.parents[node] = None;
+        }
+
+        // Seed every affected node with its best edge from the unaffected part of the tree
+        let mut heap = BinaryHeap::new();
+        for &node in &affected {
+            for (&parent, &weight) in &self.incoming[node] {
+                if in_subtree[parent] || self.distances[parent] == UNREACHABLE {
+                    continue;
+                }
+                let candidate = self.distances[parent] + weight as u64;
+                if candidate < self.distances[node] {
+                    self.distances[node] = candidate;
+                    self.parents[node] = Some(parent);
+                }
+            }
+            if self.distances[node] != UNREACHABLE {
+                heap.push(Reverse((self.distances[node], node)));
+            }
+        }
+        self.settle(heap)
+    }
+
+    // Dijkstra's algorithm from the nodes on the heap, returning the number of nodes settled.
+    fn settle(&mut self, mut heap: BinaryHeap<Reverse<(u64, usize)>>) -> usize {
+        let mut settled = 0;
+        while let Some(Reverse((distance, node))) = heap.pop() {
+            if distance > self.distances[node] {
+                continue;
+            }
+            settled += 1;
+            for (&neighbor, &weight) in &self.outgoing[node] {
+                let candidate = distance + weight as u64;
+                if candidate < self.distances[neighbor] {
+                    self.distances[neighbor] = candidate;
+                    self.parents[neighbor] = Some(node);
+                    heap.push(Reverse((candidate, neighbor)));
+                }
+            }
+        }
+        settled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::csr_graph::CsrGraph;
+    use crate::graphs::generators::{self, GeneratorConfig, Rng, Weights};
+
+    fn create_graph() -> HashMap<char, HashMap<char, u32>> {
+        let mut graph = HashMap::new();
+        graph.insert('A', HashMap::from([('B', 1), ('C', 4)]));
+        graph.insert('B', HashMap::from([('C', 2), ('D', 5)]));
+        graph.insert('C', HashMap::from([('D', 1)]));
+        graph.insert('D', HashMap::new());
+        graph
+    }
+
+    #[test]
+    fn test_updates() {
+        let mut paths = DynamicShortestPaths::new(&create_graph(), 'A');
+        assert_eq!(paths.distance(&'D'), Some(4));
+        assert_eq!(paths.path(&'D'), Some(vec!['A', 'B', 'C', 'D']));
+
+        // Not on the tree, nothing to repair
+        assert_eq!(paths.set_edge('B', 'D', 9), 0);
+        assert_eq!(paths.distance(&'D'), Some(4));
+
+        // A shortcut improves only D
+        assert_eq!(paths.set_edge('A', 'D', 2), 1);
+        assert_eq!(paths.path(&'D'), Some(vec!['A', 'D']));
+
+        assert_eq!(paths.remove_edge(&'A', &'D'), 1);
+        assert_eq!(paths.distance(&'D'), Some(4));
+        paths.set_edge('B', 'C', 10);
+        assert_eq!(paths.path(&'D'), Some(vec!['A', 'C', 'D']));
+        assert_eq!(paths.distance(&'D'), Some(5));
+
+        paths.remove_edge(&'A', &'C');
+        paths.remove_edge(&'B', &'C');
+        assert_eq!(paths.distance(&'C'), None);
+        assert_eq!(paths.path(&'D'), Some(vec!['A', 'B', 'D']));
+
+        paths.set_edge('D', 'E', 1);
+        assert_eq!(paths.distance(&'E'), Some(11));
+        assert_eq!(paths.parent(&'E'), Some(&'D'));
+    }
+
+    #[test]
+    fn test_matches_recomputation() {
+        let config = GeneratorConfig { seed: 7, directed: true, weights: Weights::Uniform { min: 1, max: 20 } };
+        let mut graph = generators::erdos_renyi(200, 0.03, &config);
+        let mut paths = DynamicShortestPaths::new(&graph, 0);
+        let mut rng = Rng::new(11);
+
+        for _ in 0..300 {
+            let from = rng.below(200) as u32;
+            let to = rng.below(200) as u32;
+            if rng.below(3) == 0 {
+                graph.entry(from).or_default().remove(&to);
+                paths.remove_edge(&from, &to);
+            } else {
+                let weight = 1 + rng.below(20) as u32;
+                graph.entry(from).or_default().insert(to, weight);
+                paths.set_edge(from, to, weight);
+            }
+
+            let csr = CsrGraph::from_weighted(&graph);
+            let expected = csr.dijkstra_distances(csr.index_of(&0).unwrap());
+            for node in 0..200 {
+                let expected = csr.index_of(&node).and_then(|index| expected[index as usize]);
+                assert_eq!(paths.distance(&node), expected);
+                if let Some(path) = paths.path(&node) {
+                    let length: u64 = path.windows(2).map(|edge| graph[&edge[0]][&edge[1]] as u64).sum();
+                    assert_eq!(Some(length), expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,504 @@
+/**
+ * Graph Generators
+ *
+ * Random and structured graphs for testing and benchmarking the search functions in this module.
+ *
+ * Every generator returns the weighted adjacency map shape used by the Dijkstra modules, with nodes
+ * numbered `0..n`. Use `to_adjacency_lists` for the unweighted shape used by `bfs_graph` and
+ * `dfs_graph`, and `relabel` to turn the numbers into other IDs such as `char`.
+ *
+ * All randomness comes from a seeded `Rng`, so the same `GeneratorConfig` always produces the same
+ * graph. The edge weights are drawn from a separate stream, so changing the weights does not change
+ * which edges are generated.
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub type WeightedGraph = HashMap<u32, HashMap<u32, u32>>;
+
+/**
+ * SplitMix64 pseudo random number generator.
+ *
+ * Small, fast and good enough for generating test data. Not suitable for cryptography.
+ */
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /**
+     * A uniformly distributed float in `[0, 1)`.
+     */
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /**
+     * A uniformly distributed integer in `[0, bound)`.
+     */
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be positive");
+        // Multiply-shift maps the 64 random bits onto the range without a slow modulo
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+}
+
+/**
+ * How edge weights are chosen.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Weights {
+    /// Every edge has the same weight.
+    Constant(u32),
+    /// Weights are drawn uniformly from `min..=max`.
+    Uniform { min: u32, max: u32 },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GeneratorConfig {
+    pub seed: u64,
+    /// Undirected graphs store every edge in both directions with the same weight.
+    pub directed: bool,
+    pub weights: Weights,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig { seed: 0, directed: false, weights: Weights::Constant(1) }
+    }
+}
+
+// Collects edges and assigns their weights from a stream independent of the structure.
+struct Builder {
+    graph: WeightedGraph,
+    directed: bool,
+    weights: Weights,
+    weight_rng: Rng,
+}
+
+impl Builder {
+    fn new(n: u32, config: &GeneratorConfig) -> Self {
+        let graph = (0..n).map(|node| (node, HashMap::new())).collect();
+        Builder {
+            graph,
+            directed: config.directed,
+            weights: config.weights,
+            weight_rng: Rng::new(config.seed ^ 0xA5A5_A5A5_A5A5_A5A5),
+        }
+    }
+
+    fn add_edge(&mut self, from: u32, to: u32) {
+        let weight = match self.weights {
+            Weights::Constant(weight) => weight,
+            Weights::Uniform { min, max } => {
+                assert!(min <= max, "weight range is empty");
+                min + self.weight_rng.below((max - min) as u64 + 1) as u32
+            }
+        };
+        self.graph.get_mut(&from).unwrap().insert(to, weight);
+        if !self.directed {
+            self.graph.get_mut(&to).unwrap().insert(from, weight);
+        }
+    }
+}
+
+/**
+ * Erdős–Rényi G(n, p)
+ *
+ * Every possible edge is present independently with probability `p`. Instead of flipping a coin for
+ * each of the O(n^2) pairs, the gap to the next present edge is drawn from a geometric distribution
+ * (Batagelj and Brandes), so sparse graphs are generated in time proportional to their size.
+ *
+ * Time Complexity: O(n + m)
+ */
+pub fn erdos_renyi(n: u32, p: f64, config: &GeneratorConfig) -> WeightedGraph {
+    assert!((0.0..=1.0).contains(&p), "p must be a probability");
+    let mut rng = Rng::new(config.seed);
+    let mut builder = Builder::new(n, config);
+    let n = n as u64;
+    // Directed graphs pick from the n * (n - 1) ordered pairs, undirected from the n * (n - 1) / 2 unordered ones.
+    let pairs = if config.directed { n * n.saturating_sub(1) } else { n * n.saturating_sub(1) / 2 };
+    if p <= 0.0 || pairs == 0 {
+        return builder.graph;
+    }
+
+    let log_q = (1.0 - p).ln();
+    let mut pair: u64 = 0;
+    // Row and column of the current pair for the undirected enumeration (column < row).
+    let (mut row, mut row_start) = (1u64, 0u64);
+    loop {
+        if p < 1.0 {
+            let skip = ((1.0 - rng.next_f64()).ln() / log_q).floor();
+            if skip >= (pairs - pair) as f64 {
+                break;
+            }
+            pair += skip as u64;
+        }
+        if pair >= pairs {
+            break;
+        }
+
+        let (from, to) = if config.directed {
+            let from = pair / (n - 1);
+            let offset = pair % (n - 1);
+            (from, if offset >= from { offset + 1 } else { offset })
+        } else {
+            while pair >= row_start + row {
+                row_start += row;
+                row += 1;
+            }
+            (row, pair - row_start)
+        };
+        builder.add_edge(from as u32, to as u32);
+        pair += 1;
+    }
+
+    builder.graph
+}
+
+/**
+ * Barabási–Albert Preferential Attachment
+ *
+ * Starts from a complete graph on `m + 1` nodes, then every new node connects to `m` distinct
+ * existing nodes chosen with probability proportional to their degree. This produces the heavy
+ * tailed degree distribution seen in many real networks. Directed graphs point from the new node
+ * to the existing ones.
+ *
+ * Time Complexity: O(n * m)
+ */
+pub fn barabasi_albert(n: u32, m: u32, config: &GeneratorConfig) -> WeightedGraph {
+    assert!(m >= 1, "every new node needs at least one edge");
+    let mut rng = Rng::new(config.seed);
+    let mut builder = Builder::new(n, config);
+    let seed_nodes = (m + 1).min(n);
+
+    // Every node appears once per incident edge, so a uniform pick is proportional to degree.
+    let mut endpoints = Vec::new();
+    for from in 0..seed_nodes {
+        for to in from + 1..seed_nodes {
+            builder.add_edge(from, to);
+            endpoints.push(from);
+            endpoints.push(to);
+        }
+    }
+
+    for node in seed_nodes..n {
+        let mut targets = Vec::with_capacity(m as usize);
+        while targets.len() < m as usize {
+            let target = endpoints[rng.below(endpoints.len() as u64) as usize];
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+        for target in targets {
+            builder.add_edge(node, target);
+            endpoints.push(node);
+            endpoints.push(target);
+        }
+    }
+
+    builder.graph
+}
+
+/**
+ * Random Geometric Graph
+ *
+ * Places `n` points uniformly in the unit square and connects every pair that is at most `radius`
+ * apart. Points are bucketed into a grid of `radius` sized cells so only neighboring cells are
+ * compared. Directed graphs get an edge from the lower to the higher numbered node.
+ *
+ * Time Complexity: O(n + m) expected
+ */
+pub fn random_geometric(n: u32, radius: f64, config: &GeneratorConfig) -> WeightedGraph {
+    assert!(radius > 0.0, "radius must be positive");
+    let mut rng = Rng::new(config.seed);
+    let mut builder = Builder::new(n, config);
+    let points: Vec<(f64, f64)> = (0..n).map(|_| (rng.next_f64(), rng.next_f64())).collect();
+
+    let cells_per_side = ((1.0 / radius).floor() as usize).clamp(1, 1 << 12);
+    let cell_of = |x: f64| ((x * cells_per_side as f64) as usize).min(cells_per_side - 1);
+    let mut cells: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
+    for (node, &(x, y)) in points.iter().enumerate() {
+        cells.entry((cell_of(x), cell_of(y))).or_default().push(node as u32);
+    }
+
+    for (from, &(x, y)) in points.iter().enumerate() {
+        let (cx, cy) = (cell_of(x), cell_of(y));
+        let mut close = Vec::new();
+        for nx in cx.saturating_sub(1)..=(cx + 1).min(cells_per_side - 1) {
+            for ny in cy.saturating_sub(1)..=(cy + 1).min(cells_per_side - 1) {
+                for &to in cells.get(&(nx, ny)).into_iter().flatten() {
+                    let (tx, ty) = points[to as usize];
+                    if (to as usize) > from && (x - tx).hypot(y - ty) <= radius {
+                        close.push(to);
+                    }
+                }
+            }
+        }
+        // Sort so the weight stream is consumed in the same order on every run
+        close.sort_unstable();
+        for to in close {
+            builder.add_edge(from as u32, to);
+        }
+    }
+
+    builder.graph
+}
+
+/**
+ * Grid Graph
+ *
+ * A `rows` by `columns` lattice where node `r * columns + c` connects to its right and lower
+ * neighbors. Directed graphs only point right and down.
+ */
+pub fn grid(rows: u32, columns: u32, config: &GeneratorConfig) -> WeightedGraph {
+    let mut builder = Builder::new(rows * columns, config);
+    for row in 0..rows {
+        for column in 0..columns {
+            let node = row * columns + column;
+            if column + 1 < columns {
+                builder.add_edge(node, node + 1);
+            }
+            if row + 1 < rows {
+                builder.add_edge(node, node + columns);
+            }
+        }
+    }
+    builder.graph
+}
+
+/**
+ * Complete Graph
+ *
+ * Every pair of distinct nodes is connected. Directed graphs have edges in both directions, each
+ * with its own weight.
+ */
+pub fn complete(n: u32, config: &GeneratorConfig) -> WeightedGraph {
+    let mut builder = Builder::new(n, config);
+    for from in 0..n {
+        let first = if config.directed { 0 } else { from + 1 };
+        for to in first..n {
+            if to != from {
+                builder.add_edge(from, to);
+            }
+        }
+    }
+    builder.graph
+}
+
+/**
+ * Path Graph
+ *
+ * `0 - 1 - 2 - ... - (n - 1)`
+ */
+pub fn path(n: u32, config: &GeneratorConfig) -> WeightedGraph {
+    let mut builder = Builder::new(n, config);
+    for node in 1..n {
+        builder.add_edge(node - 1, node);
+    }
+    builder.graph
+}
+
+/**
+ * Star Graph
+ *
+ * Node 0 is the center and is connected to every other node. Directed graphs point outward.
+ */
+pub fn star(n: u32, config: &GeneratorConfig) -> WeightedGraph {
+    let mut builder = Builder::new(n, config);
+    for node in 1..n {
+        builder.add_edge(0, node);
+    }
+    builder.graph
+}
+
+/**
+ * Random Tree
+ *
+ * A random recursive tree rooted at node 0: every node `i > 0` is attached to a uniformly chosen
+ * node in `0..i`. Directed graphs point from parent to child.
+ */
+pub fn tree(n: u32, config: &GeneratorConfig) -> WeightedGraph {
+    let mut rng = Rng::new(config.seed);
+    let mut builder = Builder::new(n, config);
+    for node in 1..n {
+        let parent = rng.below(node as u64) as u32;
+        builder.add_edge(parent, node);
+    }
+    builder.graph
+}
+
+/**
+ * Random Directed Acyclic Graph
+ *
+ * Shuffles the nodes into a random topological order and adds each forward edge with probability
+ * `p`. The result is always directed, whatever `config.directed` says.
+ *
+ * Time Complexity: O(n^2)
+ */
+pub fn random_dag(n: u32, p: f64, config: &GeneratorConfig) -> WeightedGraph {
+    assert!((0.0..=1.0).contains(&p), "p must be a probability");
+    let mut rng = Rng::new(config.seed);
+    let mut builder = Builder::new(n, &GeneratorConfig { directed: true, ..*config });
+
+    // Fisher-Yates shuffle so the topological order is not simply 0..n
+    let mut order: Vec<u32> = (0..n).collect();
+    for i in (1..order.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        order.swap(i, j);
+    }
+
+    for i in 0..order.len() {
+        for j in i + 1..order.len() {
+            if rng.next_f64() < p {
+                builder.add_edge(order[i], order[j]);
+            }
+        }
+    }
+    builder.graph
+}
+
+/**
+ * Drop the weights, giving the adjacency list shape used by `bfs_graph` and `dfs_graph`.
+ *
+ * Neighbor lists are sorted so the result does not depend on `HashMap` iteration order.
+ */
+pub fn to_adjacency_lists(graph: &WeightedGraph) -> HashMap<u32, Vec<u32>> {
+    graph
+        .iter()
+        .map(|(&node, neighbors)| {
+            let mut neighbors: Vec<u32> = neighbors.keys().copied().collect();
+            neighbors.sort_unstable();
+            (node, neighbors)
+        })
+        .collect()
+}
+
+/**
+ * Rename every node, for example to run the `char` based searches on a generated graph.
+ */
+pub fn relabel<N, F>(graph: &WeightedGraph, mut label: F) -> HashMap<N, HashMap<N, u32>>
+where
+    N: Hash + Eq,
+    F: FnMut(u32) -> N,
+{
+    graph
+        .iter()
+        .map(|(&node, neighbors)| {
+            let neighbors = neighbors.iter().map(|(&to, &weight)| (label(to), weight)).collect();
+            (label(node), neighbors)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::bfs_graph::breadth_first_search;
+    use crate::graphs::csr_graph::CsrGraph;
+
+    fn edge_count(graph: &WeightedGraph) -> usize {
+        graph.values().map(|neighbors| neighbors.len()).sum()
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let config = GeneratorConfig { seed: 7, weights: Weights::Uniform { min: 1, max: 10 }, ..Default::default() };
+        assert_eq!(erdos_renyi(200, 0.05, &config), erdos_renyi(200, 0.05, &config));
+        assert_eq!(barabasi_albert(200, 3, &config), barabasi_albert(200, 3, &config));
+        assert_eq!(random_geometric(200, 0.1, &config), random_geometric(200, 0.1, &config));
+        assert_ne!(erdos_renyi(200, 0.05, &config), erdos_renyi(200, 0.05, &GeneratorConfig { seed: 8, ..config }));
+
+        // Changing the weights keeps the same edges
+        let constant = GeneratorConfig { weights: Weights::Constant(1), ..config };
+        assert_eq!(to_adjacency_lists(&erdos_renyi(200, 0.05, &config)), to_adjacency_lists(&erdos_renyi(200, 0.05, &constant)));
+        let weights: Vec<u32> = erdos_renyi(200, 0.05, &config).values().flat_map(|n| n.values().copied()).collect();
+        assert!(weights.iter().all(|w| (1..=10).contains(w)));
+    }
+
+    #[test]
+    fn test_structured_graphs() {
+        let undirected = GeneratorConfig::default();
+        let directed = GeneratorConfig { directed: true, ..Default::default() };
+
+        assert_eq!(edge_count(&complete(6, &undirected)), 30);
+        assert_eq!(edge_count(&complete(6, &directed)), 30);
+        assert_eq!(edge_count(&path(6, &undirected)), 10);
+        assert_eq!(edge_count(&star(6, &directed)), 5);
+        assert_eq!(edge_count(&tree(50, &directed)), 49);
+        // 3 rows of 3 right edges and 2 rows of 4 down edges
+        assert_eq!(edge_count(&grid(3, 4, &directed)), 17);
+        assert_eq!(edge_count(&erdos_renyi(10, 1.0, &undirected)), 90);
+        assert_eq!(edge_count(&erdos_renyi(10, 1.0, &directed)), 90);
+        assert_eq!(edge_count(&erdos_renyi(10, 0.0, &directed)), 0);
+        // Clique of 4 nodes (6 edges) plus 3 edges for each of the 6 later nodes
+        assert_eq!(edge_count(&barabasi_albert(10, 3, &directed)), 6 + 6 * 3);
+    }
+
+    #[test]
+    fn test_random_graphs_shape() {
+        let config = GeneratorConfig { seed: 42, ..Default::default() };
+        for graph in [erdos_renyi(300, 0.02, &config), barabasi_albert(300, 2, &config), random_geometric(300, 0.1, &config)] {
+            for (from, neighbors) in &graph {
+                assert!(!neighbors.contains_key(from));
+                for (to, weight) in neighbors {
+                    assert_eq!(graph[to].get(from), Some(weight));
+                }
+            }
+        }
+
+        // Kahn's algorithm can only order every node if there is no cycle
+        let dag = random_dag(100, 0.1, &config);
+        let mut position = HashMap::new();
+        let mut remaining: HashMap<u32, usize> = dag.keys().map(|&node| (node, 0)).collect();
+        for neighbors in dag.values() {
+            for to in neighbors.keys() {
+                *remaining.get_mut(to).unwrap() += 1;
+            }
+        }
+        let mut ready: Vec<u32> = remaining.iter().filter(|(_, &d)| d == 0).map(|(&n, _)| n).collect();
+        while let Some(node) = ready.pop() {
+            position.insert(node, position.len());
+            for to in dag[&node].keys() {
+                let degree = remaining.get_mut(to).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(*to);
+                }
+            }
+        }
+        assert_eq!(position.len(), 100);
+    }
+
+    #[test]
+    fn test_stress_breadth_first_search() {
+        let config = GeneratorConfig { seed: 3, directed: true, ..Default::default() };
+        let graph = erdos_renyi(500, 0.004, &config);
+        let label = |node: u32| char::from_u32(node + 0x100).unwrap();
+        let lists: HashMap<char, Vec<char>> = to_adjacency_lists(&graph)
+            .into_iter()
+            .map(|(node, neighbors)| (label(node), neighbors.into_iter().map(label).collect()))
+            .collect();
+        let csr = CsrGraph::from_adjacency(&lists);
+        let start = csr.index_of(&label(0)).unwrap();
+        let distances = csr.bfs_distances(start);
+
+        for needle in 0..500 {
+            let path = breadth_first_search(&lists, label(0), label(needle));
+            let expected = distances[csr.index_of(&label(needle)).unwrap() as usize];
+            assert_eq!(path.map(|path| path.len() as u32 - 1), expected);
+        }
+    }
+}
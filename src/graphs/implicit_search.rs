@@ -0,0 +1,405 @@
+/**
+ * Search Over Implicit Graphs
+ *
+ * Breadth first search, depth first search, Dijkstra's algorithm and A* for graphs that are never
+ * stored. Instead of a `HashMap` of adjacency lists, each search takes:
+ *
+ * - a start state, which can be any `Hash + Eq + Clone` type (a puzzle board, a robot pose, ...),
+ * - a `successors` closure that lists the states reachable in one step (with their step cost for
+ *   the weighted searches),
+ * - a `goal` predicate.
+ *
+ * States are generated lazily as the search reaches them, so the part of the graph beyond the goal is
+ * never built. `max_visited` caps the number of distinct states a search may discover, the start
+ * included; a search that hits the cap gives up and returns `None`, the same as when no goal is
+ * reachable.
+ *
+ * Every search returns the path from the start to the first goal found, both included.
+ */
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+// Discovered states, numbered in discovery order, with the state they were discovered from.
+struct Visited<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    parents: Vec<usize>,
+    limit: usize,
+}
+
+impl<S: Hash + Eq + Clone> Visited<S> {
+    fn new(start: S, max_visited: Option<usize>) -> Self {
+        let limit = max_visited.unwrap_or(usize::MAX);
+        let mut visited = Visited { states: Vec::new(), index: HashMap::new(), parents: Vec::new(), limit };
+        visited.insert(start, 0);
+        visited
+    }
+
+    // Returns the index of a new state, `None` if it was discovered before.
+    fn insert(&mut self, state: S, parent: usize) -> Option<usize> {
+        if self.index.contains_key(&state) {
+            return None;
+        }
+        let index = self.states.len();
+        self.index.insert(state.clone(), index);
+        self.states.push(state);
+        self.parents.push(parent);
+        Some(index)
+    }
+
+    // Whether more states were discovered than allowed. Checked before a new state is used at all,
+    // so the search gives up on the first state over the limit.
+    fn exceeded(&self) -> bool {
+        self.states.len() > self.limit
+    }
+
+    fn path(&self, mut index: usize) -> Vec<S> {
+        let mut path = vec![self.states[index].clone()];
+        while index != 0 {
+            index = self.parents[index];
+            path.push(self.states[index].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/**
+ * Breadth First Search
+ *
+ * Finds a path with the fewest steps.
+ *
+ * Time Complexity: O(V + E) over the states discovered
+ * Space Complexity: O(V)
+ *
+ * @param start The state to search from
+ * @param successors The states reachable from a state in one step
+ * @param goal Whether a state is a goal
+ * @param max_visited The maximum number of states to discover, unlimited if `None`
+ * @return The path from `start` to the nearest goal
+ */
+pub fn bfs<S, F, I, G>(start: S, mut successors: F, mut goal: G, max_visited: Option<usize>) -> Option<Vec<S>>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new(start, max_visited);
+    if visited.exceeded() {
+        return None;
+    }
+    if goal(&visited.states[0]) {
+        return Some(visited.path(0));
+    }
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(current) = queue.pop_front() {
+        for next in successors(&visited.states[current]) {
+            if let Some(index) = visited.insert(next, current) {
+                if visited.exceeded() {
+                    return None;
+                }
+                if goal(&visited.states[index]) {
+                    return Some(visited.path(index));
+                }
+                queue.push_back(index);
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * Depth First Search
+ *
+ * Follows the first successor of each state as deep as possible before backtracking, asking for
+ * the successors of a state one at a time. Uses an explicit stack, so deep searches cannot overflow
+ * the call stack. The path found is not necessarily the shortest.
+ *
+ * Time Complexity: O(V + E) over the states discovered
+ * Space Complexity: O(V)
+ *
+ * @param start The state to search from
+ * @param successors The states reachable from a state in one step
+ * @param goal Whether a state is a goal
+ * @param max_visited The maximum number of states to discover, unlimited if `None`
+ * @return The path from `start` to the first goal reached
+ */
+pub fn dfs<S, F, I, G>(start: S, mut successors: F, mut goal: G, max_visited: Option<usize>) -> Option<Vec<S>>
+where
+    S: Hash + Eq + Clone,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new(start, max_visited);
+    if visited.exceeded() {
+        return None;
+    }
+    if goal(&visited.states[0]) {
+        return Some(visited.path(0));
+    }
+    let mut stack = vec![(0, successors(&visited.states[0]).into_iter())];
+
+    while let Some((current, next_states)) = stack.last_mut() {
+        let current = *current;
+        match next_states.next() {
+            Some(next) => {
+                if let Some(index) = visited.insert(next, current) {
+                    if visited.exceeded() {
+                        return None;
+                    }
+                    if goal(&visited.states[index]) {
+                        return Some(visited.path(index));
+                    }
+                    stack.push((index, successors(&visited.states[index]).into_iter()));
+                }
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    None
+}
+
+/**
+ * Dijkstra's Algorithm
+ *
+ * Finds a cheapest path when every step has a non-negative cost. `C` can be any ordered cost type
+ * whose `Default` is zero, such as the unsigned integers.
+ *
+ * Time Complexity: O((V + E) log V) over the states discovered
+ * Space Complexity: O(V + E)
+ *
+ * @param start The state to search from
+ * @param successors The states reachable from a state in one step, with the cost of the step
+ * @param goal Whether a state is a goal
+ * @param max_visited The maximum number of states to discover, unlimited if `None`
+ * @return The path from `start` to the cheapest goal and its cost
+ */
+pub fn dijkstra<S, C, F, I, G>(start: S, successors: F, goal: G, max_visited: Option<usize>) -> Option<(Vec<S>, C)>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, successors, |_| C::default(), goal, max_visited)
+}
+
+/**
+ * A* Search
+ *
+ * Dijkstra's algorithm guided by a `heuristic` estimate of the remaining cost to a goal: states are
+ * expanded in order of cost so far plus estimate. The path found is the cheapest as long as the
+ * heuristic never overestimates (is admissible) and is consistent, e.g. the Manhattan distance on a
+ * grid.
+ *
+ * Time Complexity: O((V + E) log V) over the states discovered, far fewer with a good heuristic
+ * Space Complexity: O(V + E)
+ *
+ * @param start The state to search from
+ * @param successors The states reachable from a state in one step, with the cost of the step
+ * @param heuristic An estimate of the cost from a state to the nearest goal
+ * @param goal Whether a state is a goal
+ * @param max_visited The maximum number of states to discover, unlimited if `None`
+ * @return The path from `start` to the cheapest goal and its cost
+ */
+pub fn astar<S, C, F, I, H, G>(
+    start: S,
+    mut successors: F,
+    mut heuristic: H,
+    mut goal: G,
+    max_visited: Option<usize>,
+) -> Option<(Vec<S>, C)>
+where
+    S: Hash + Eq + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let zero = C::default();
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), zero, 0))]);
+    let mut visited = Visited::new(start, max_visited);
+    if visited.exceeded() {
+        return None;
+    }
+    let mut costs = vec![zero];
+    let mut closed = vec![false];
+
+    while let Some(Reverse((_, cost, current))) = heap.pop() {
+        if closed[current] || cost > costs[current] {
+            continue;
+        }
+        if goal(&visited.states[current]) {
+            return Some((visited.path(current), cost));
+        }
+        closed[current] = true;
+
+        for (next, step) in successors(&visited.states[current]) {
+            let candidate = cost + step;
+            let index = match visited.index.get(&next) {
+                Some(&index) if closed[index] || candidate >= costs[index] => continue,
+                Some(&index) => {
+                    visited.parents[index] = current;
+                    costs[index] = candidate;
+                    index
+                }
+                None => {
+                    let estimate = heuristic(&next);
+                    let index = visited.insert(next, current).unwrap();
+                    if visited.exceeded() {
+                        return None;
+                    }
+                    costs.push(candidate);
+                    closed.push(false);
+                    heap.push(Reverse((candidate + estimate, candidate, index)));
+                    continue;
+                }
+            };
+            let estimate = heuristic(&visited.states[index]);
+            heap.push(Reverse((candidate + estimate, candidate, index)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Board = [u8; 9];
+
+    const SOLVED: Board = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+
+    // Slide a tile into the blank (0) square of the 8-puzzle
+    fn moves(board: &Board) -> Vec<Board> {
+        let blank = board.iter().position(|&tile| tile == 0).unwrap();
+        let (row, column) = (blank / 3, blank % 3);
+        let mut next = Vec::new();
+        for (dr, dc) in [(-1i32, 0i32), (1, 0), (0, -1), (0, 1)] {
+            let (r, c) = (row as i32 + dr, column as i32 + dc);
+            if (0..3).contains(&r) && (0..3).contains(&c) {
+                let mut board = *board;
+                board.swap(blank, (r * 3 + c) as usize);
+                next.push(board);
+            }
+        }
+        next
+    }
+
+    fn manhattan(board: &Board) -> u32 {
+        board
+            .iter()
+            .enumerate()
+            .filter(|&(_, &tile)| tile != 0)
+            .map(|(i, &tile)| {
+                let target = tile as usize - 1;
+                ((i / 3).abs_diff(target / 3) + (i % 3).abs_diff(target % 3)) as u32
+            })
+            .sum()
+    }
+
+    fn assert_valid(path: &[Board], start: Board) {
+        assert_eq!(path[0], start);
+        assert_eq!(*path.last().unwrap(), SOLVED);
+        for step in path.windows(2) {
+            assert!(moves(&step[0]).contains(&step[1]));
+        }
+    }
+
+    #[test]
+    fn test_eight_puzzle() {
+        let start = [8, 6, 7, 2, 5, 4, 3, 0, 1];
+        let is_solved = |board: &Board| *board == SOLVED;
+        let with_cost = |board: &Board| moves(board).into_iter().map(|next| (next, 1u32));
+
+        let shortest = bfs(start, moves, is_solved, None).unwrap();
+        assert_valid(&shortest, start);
+        // One of the two hardest 8-puzzle positions
+        assert_eq!(shortest.len() - 1, 31);
+
+        let (path, cost) = astar(start, with_cost, manhattan, is_solved, None).unwrap();
+        assert_valid(&path, start);
+        assert_eq!(cost, 31);
+        assert_eq!(path.len() - 1, 31);
+
+        let (_, cost) = dijkstra(start, with_cost, is_solved, None).unwrap();
+        assert_eq!(cost, 31);
+
+        let path = dfs(start, moves, is_solved, None).unwrap();
+        assert_valid(&path, start);
+    }
+
+    #[test]
+    fn test_max_visited() {
+        let start = [1, 2, 3, 4, 5, 6, 0, 7, 8];
+        let is_solved = |board: &Board| *board == SOLVED;
+        assert_eq!(bfs(start, moves, is_solved, Some(1000)).unwrap().len(), 3);
+        assert_eq!(bfs(start, moves, is_solved, Some(2)), None);
+        // The start counts, so a limit of 1 only allows the start and 0 not even that
+        assert_eq!(bfs(SOLVED, moves, is_solved, Some(1)), Some(vec![SOLVED]));
+        assert_eq!(bfs(SOLVED, moves, is_solved, Some(0)), None);
+        let one_step = [1, 2, 3, 4, 5, 6, 7, 0, 8];
+        let with_cost = |board: &Board| moves(board).into_iter().map(|next| (next, 1u32));
+        for limit in [Some(0), Some(1)] {
+            assert_eq!(bfs(one_step, moves, is_solved, limit), None);
+            assert_eq!(dfs(one_step, moves, is_solved, limit), None);
+            assert_eq!(astar(one_step, with_cost, manhattan, is_solved, limit), None);
+            assert_eq!(dijkstra(one_step, with_cost, is_solved, limit), None);
+        }
+        // The solved board is the third of the three successors
+        assert_eq!(bfs(one_step, moves, is_solved, Some(4)), Some(vec![one_step, SOLVED]));
+        assert_eq!(bfs(one_step, moves, is_solved, Some(3)), None);
+        assert_eq!(astar(one_step, with_cost, manhattan, is_solved, Some(4)).unwrap().1, 1);
+
+        // The goal is checked on the start and on every state discovered, so counting its calls
+        // gives the number of states a search needs: exactly that many is enough, one fewer is not
+        let mut discovered = 0;
+        let counting = |board: &Board| {
+            discovered += 1;
+            *board == SOLVED
+        };
+        bfs(start, moves, counting, None).unwrap();
+        assert!(bfs(start, moves, is_solved, Some(discovered)).is_some());
+        assert_eq!(bfs(start, moves, is_solved, Some(discovered - 1)), None);
+        let mut discovered = 0;
+        let counting = |board: &Board| {
+            discovered += 1;
+            *board == SOLVED
+        };
+        dfs(start, moves, counting, None).unwrap();
+        assert!(dfs(start, moves, is_solved, Some(discovered)).is_some());
+        assert_eq!(dfs(start, moves, is_solved, Some(discovered - 1)), None);
+
+        // Half of all boards are unreachable, so the search has to give up
+        let unsolvable = [2, 1, 3, 4, 5, 6, 7, 8, 0];
+        assert_eq!(dfs(unsolvable, moves, is_solved, Some(5000)), None);
+        assert_eq!(bfs(unsolvable, moves, is_solved, None), None);
+    }
+
+    #[test]
+    fn test_weighted_infinite_graph() {
+        // From 1, reach 100 by doubling (cost 3) or adding one (cost 1); the state space is unbounded
+        let successors = |&n: &u64| [(n * 2, 3u64), (n + 1, 1)];
+        let (path, cost) = dijkstra(1, successors, |&n| n == 100, None).unwrap();
+        assert_eq!((path[0], *path.last().unwrap()), (1, 100));
+        assert_eq!(cost, 18);
+        let steps: u64 = path.windows(2).map(|step| if step[1] == step[0] + 1 { 1 } else { 3 }).sum();
+        assert_eq!(steps, cost);
+
+        let (_, cost) = astar(1, successors, |_| 0, |&n| n == 100, None).unwrap();
+        assert_eq!(cost, 18);
+        assert_eq!(bfs(1u64, |&n| [n * 2, n + 1], |&n| n == 100, None).unwrap().len(), 9);
+    }
+}
@@ -0,0 +1,402 @@
+/**
+ * Minimum Cost Flow
+ *
+ * A flow network is a directed graph where every edge has a capacity (how much can flow through it)
+ * and a cost per unit of flow. The problem is to send flow from a source to a sink as cheaply as
+ * possible, either a required amount or as much as the network allows.
+ *
+ * Successive Shortest Paths
+ *
+ * Flow is pushed one path at a time, always along the cheapest path in the residual network: the
+ * remaining capacity of every edge, plus a reverse edge with negated cost for every unit already sent
+ * (sending flow back cancels it and refunds its cost). Each node carries a potential, and edge costs
+ * are reduced to `cost + potential(from) - potential(to)`, which keeps them non-negative so every
+ * shortest path can be found with the heap based Dijkstra's algorithm. Negative edge costs are
+ * allowed, the first potentials then come from Bellman-Ford. With a negative cost cycle reachable
+ * from the source there is no cheapest flow, and the functions return `None`. They also do when the
+ * total cost does not fit in an `i64`, e.g. with a `u64::MAX` capacity used as unlimited on an edge
+ * that has a cost, or with a path whose edge costs add up to more than an `i64` holds.
+ *
+ * Time Complexity: O(F * E log V) for a total flow F, plus O(V * E) with negative costs
+ * Space Complexity: O(V + E)
+ */
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+// Distances and potentials are `i128`, which the cost of any simple path of `i64` edge costs fits in
+const UNREACHABLE: i128 = i128::MAX;
+
+// Edge `2 * i` is the `i`-th edge added and edge `2 * i + 1` its reverse.
+#[derive(Clone)]
+struct ResidualEdge {
+    to: usize,
+    capacity: u64,
+    cost: i64,
+}
+
+pub struct FlowNetwork<N> {
+    ids: Vec<N>,
+    index: HashMap<N, usize>,
+    edges: Vec<ResidualEdge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+/**
+ * The result of a flow computation. `edge_flows[i]` is the flow on the `i`-th edge added to the
+ * network, i.e. the edge whose id was returned by `add_edge`.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flow {
+    pub flow: u64,
+    pub cost: i64,
+    pub edge_flows: Vec<u64>,
+}
+
+impl<N: Hash + Eq + Clone> Default for FlowNetwork<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Hash + Eq + Clone> FlowNetwork<N> {
+    pub fn new() -> Self {
+        FlowNetwork { ids: Vec::new(), index: HashMap::new(), edges: Vec::new(), adjacency: Vec::new() }
+    }
+
+    /**
+     * Add a directed edge that carries up to `capacity` units at `cost` per unit. Parallel edges are
+     * allowed. Unknown nodes are added.
+     *
+     * @return The id of the edge, its index in `Flow::edge_flows`
+     */
+    pub fn add_edge(&mut self, from: N, to: N, capacity: u64, cost: i64) -> usize {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        let id = self.edges.len() / 2;
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(ResidualEdge { to, capacity, cost });
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(ResidualEdge { to: from, capacity: 0, cost: -cost });
+        id
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len() / 2
+    }
+
+    /**
+     * Send as much flow as possible from `source` to `sink`, at the lowest cost for that amount.
+     *
+     * @return The flow, `None` if there is a negative cost cycle or the cost overflows
+     */
+    pub fn min_cost_max_flow(&self, source: &N, sink: &N) -> Option<Flow> {
+        self.solve(source, sink, u64::MAX)
+    }
+
+    /**
+     * Send exactly `required` units from `source` to `sink` at the lowest cost.
+     *
+     * @return The flow, `None` if the network cannot carry `required` units, there is a negative
+     * cost cycle or the cost overflows
+     */
+    pub fn min_cost_flow(&self, source: &N, sink: &N, required: u64) -> Option<Flow> {
+        let flow = self.solve(source, sink, required)?;
+        (flow.flow == required).then_some(flow)
+    }
+
+    fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.index.get(&node) {
+            return index;
+        }
+        self.index.insert(node.clone(), self.ids.len());
+        self.ids.push(node);
+        self.adjacency.push(Vec::new());
+        self.ids.len() - 1
+    }
+
+    fn solve(&self, source: &N, sink: &N, limit: u64) -> Option<Flow> {
+        let mut edges = self.edges.clone();
+        let mut flow = Flow { flow: 0, cost: 0, edge_flows: vec![0; self.edge_count()] };
+        let (source, sink) = match (self.index.get(source), self.index.get(sink)) {
+            (Some(&source), Some(&sink)) if source != sink => (source, sink),
+            _ => return Some(flow),
+        };
+
+        // A unit cost times a `u64` amount always fits in an `i128`, only the total can overflow
+        let mut cost: i128 = 0;
+        let mut potentials = self.initial_potentials(source)?;
+        while flow.flow < limit {
+            let (distances, via) = self.dijkstra(&edges, &potentials, source);
+            if distances[sink] == UNREACHABLE {
+                break;
+            }
+            for (potential, &distance) in potentials.iter_mut().zip(&distances) {
+                if distance != UNREACHABLE {
+                    *potential += distance;
+                }
+            }
+
+            // Walk the path back from the sink to find its bottleneck, then push that much
+            let mut amount = limit - flow.flow;
+            let mut node = sink;
+            while node != source {
+                let edge = via[node];
+                amount = amount.min(edges[edge].capacity);
+                node = edges[edge ^ 1].to;
+            }
+            let mut node = sink;
+            while node != source {
+                let edge = via[node];
+                edges[edge].capacity -= amount;
+                edges[edge ^ 1].capacity += amount;
+                cost = cost.checked_add(amount as i128 * edges[edge].cost as i128)?;
+                node = edges[edge ^ 1].to;
+            }
+            flow.flow += amount;
+        }
+
+        for (id, edge_flow) in flow.edge_flows.iter_mut().enumerate() {
+            *edge_flow = edges[2 * id + 1].capacity;
+        }
+        flow.cost = i64::try_from(cost).ok()?;
+        Some(flow)
+    }
+
+    // Bellman-Ford distances from the source when some costs are negative, all zeros otherwise.
+    // `None` if a negative cycle is reachable from the source.
+    fn initial_potentials(&self, source: usize) -> Option<Vec<i128>> {
+        let n = self.node_count();
+        if self.edges.iter().step_by(2).all(|edge| edge.cost >= 0) {
+            return Some(vec![0; n]);
+        }
+
+        let mut distances = vec![UNREACHABLE; n];
+        distances[source] = 0;
+        for round in 0..n {
+            let mut changed = false;
+            for from in 0..n {
+                if distances[from] == UNREACHABLE {
+                    continue;
+                }
+                for &edge in &self.adjacency[from] {
+                    let ResidualEdge { to, capacity, cost } = self.edges[edge];
+                    let next = distances[from] + cost as i128;
+                    if capacity > 0 && next < distances[to] {
+                        distances[to] = next;
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+            // Still improving after n - 1 rounds
+            if round + 1 == n {
+                return None;
+            }
+        }
+        // Unreachable nodes never take part in a path, any potential will do
+        Some(distances.into_iter().map(|distance| if distance == UNREACHABLE { 0 } else { distance }).collect())
+    }
+
+    // Dijkstra's algorithm on the reduced costs, returning distances and the edge into every node.
+    fn dijkstra(&self, edges: &[ResidualEdge], potentials: &[i128], source: usize) -> (Vec<i128>, Vec<usize>) {
+        let n = self.node_count();
+        let mut distances = vec![UNREACHABLE; n];
+        let mut via = vec![usize::MAX; n];
+        let mut heap = BinaryHeap::from([Reverse((0, source))]);
+        distances[source] = 0;
+
+        while let Some(Reverse((distance, node))) = heap.pop() {
+            if distance > distances[node] {
+                continue;
+            }
+            for &edge in &self.adjacency[node] {
+                let ResidualEdge { to, capacity, cost } = edges[edge];
+                if capacity == 0 {
+                    continue;
+                }
+                let next = distance + cost as i128 + potentials[node] - potentials[to];
+                if next < distances[to] {
+                    distances[to] = next;
+                    via[to] = edge;
+                    heap.push(Reverse((next, to)));
+                }
+            }
+        }
+
+        (distances, via)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::Rng;
+
+    fn create_test_network() -> FlowNetwork<char> {
+        let mut network = FlowNetwork::new();
+        network.add_edge('S', 'A', 2, 1);
+        network.add_edge('S', 'B', 1, 2);
+        network.add_edge('A', 'B', 1, 1);
+        network.add_edge('A', 'T', 1, 3);
+        network.add_edge('B', 'T', 2, 1);
+        network
+    }
+
+    // The flow is feasible and has no negative cycle left in the residual network, which proves it is
+    // the cheapest flow of its amount. A maximum flow also leaves no path from source to sink.
+    fn assert_optimal(network: &FlowNetwork<u32>, result: &Flow, source: usize, sink: usize, maximum: bool) {
+        let n = network.node_count();
+        let mut balance = vec![0i64; n];
+        let mut residual = Vec::new();
+        let mut cost = 0;
+        for (id, &edge_flow) in result.edge_flows.iter().enumerate() {
+            let forward = &network.edges[2 * id];
+            let from = network.edges[2 * id + 1].to;
+            assert!(edge_flow <= forward.capacity);
+            balance[from] -= edge_flow as i64;
+            balance[forward.to] += edge_flow as i64;
+            cost += edge_flow as i64 * forward.cost;
+            if edge_flow < forward.capacity {
+                residual.push((from, forward.to, forward.cost));
+            }
+            if edge_flow > 0 {
+                residual.push((forward.to, from, -forward.cost));
+            }
+        }
+        assert_eq!(cost, result.cost);
+        for (node, &balance) in balance.iter().enumerate() {
+            let expected = if node == source { -(result.flow as i64) } else if node == sink { result.flow as i64 } else { 0 };
+            assert_eq!(balance, expected);
+        }
+
+        // Bellman-Ford from a virtual node connected to every node: any update in round n is a negative cycle
+        let mut distances = vec![0i64; n];
+        for _ in 0..n {
+            for &(from, to, cost) in &residual {
+                distances[to] = distances[to].min(distances[from] + cost);
+            }
+        }
+        for &(from, to, cost) in &residual {
+            assert!(distances[from] + cost >= distances[to], "negative cycle in the residual network");
+        }
+
+        let mut reached = vec![false; n];
+        reached[source] = true;
+        for _ in 0..n {
+            for &(from, to, _) in &residual {
+                reached[to] |= reached[from];
+            }
+        }
+        assert_eq!(reached[sink], !maximum);
+    }
+
+    #[test]
+    fn test_min_cost_flow() {
+        let network = create_test_network();
+        let flow = network.min_cost_max_flow(&'S', &'T').unwrap();
+        assert_eq!(flow.flow, 3);
+        assert_eq!(flow.cost, 10);
+        assert_eq!(flow.edge_flows, vec![2, 1, 1, 1, 2]);
+
+        let flow = network.min_cost_flow(&'S', &'T', 2).unwrap();
+        assert_eq!(flow.cost, 6);
+        assert_eq!(flow.edge_flows, vec![1, 1, 1, 0, 2]);
+        assert_eq!(network.min_cost_flow(&'S', &'T', 4), None);
+        assert_eq!(network.min_cost_max_flow(&'T', &'S').unwrap().flow, 0);
+        assert_eq!(network.min_cost_max_flow(&'S', &'Z').unwrap().flow, 0);
+    }
+
+    #[test]
+    fn test_negative_costs() {
+        // An assignment problem: maximize the total value by using negated values as costs
+        let values = [[7, 5, 9], [8, 6, 3], [4, 8, 6]];
+        let mut network = FlowNetwork::new();
+        for (worker, row) in values.iter().enumerate() {
+            network.add_edge("source".to_string(), format!("worker {}", worker), 1, 0);
+            network.add_edge(format!("job {}", worker), "sink".to_string(), 1, 0);
+            for (job, &value) in row.iter().enumerate() {
+                network.add_edge(format!("worker {}", worker), format!("job {}", job), 1, -value);
+            }
+        }
+        let flow = network.min_cost_max_flow(&"source".to_string(), &"sink".to_string()).unwrap();
+        assert_eq!(flow.flow, 3);
+        // worker 0 -> job 2, worker 1 -> job 0, worker 2 -> job 1
+        assert_eq!(flow.cost, -25);
+
+        // A cycle that lowers the cost every time around has no cheapest flow
+        let mut network = create_test_network();
+        network.add_edge('A', 'C', 1, -4);
+        network.add_edge('C', 'A', 1, 2);
+        assert_eq!(network.min_cost_max_flow(&'S', &'T'), None);
+        assert_eq!(network.min_cost_flow(&'S', &'T', 1), None);
+    }
+
+    #[test]
+    fn test_unlimited_capacity() {
+        let mut network = FlowNetwork::new();
+        network.add_edge('S', 'T', u64::MAX, 0);
+        assert_eq!(network.min_cost_max_flow(&'S', &'T').unwrap().flow, u64::MAX);
+        let id = network.add_edge('S', 'T', 1, -3);
+        let flow = network.min_cost_flow(&'S', &'T', u64::MAX).unwrap();
+        assert_eq!((flow.cost, flow.edge_flows[id]), (-3, 1));
+
+        // u64::MAX units at a cost of 2 cost more than an i64 can hold
+        let mut network = FlowNetwork::new();
+        network.add_edge('S', 'T', u64::MAX, 2);
+        assert_eq!(network.min_cost_max_flow(&'S', &'T'), None);
+        assert_eq!(network.min_cost_flow(&'S', &'T', 1000).unwrap().cost, 2000);
+    }
+
+    #[test]
+    fn test_path_cost_overflow() {
+        // A single path whose cost does not fit in an i64, with positive and with negative costs
+        for cost in [i64::MAX / 2 + 10, i64::MIN / 2 - 10] {
+            let mut network = FlowNetwork::new();
+            network.add_edge('S', 'A', 1, cost);
+            network.add_edge('A', 'T', 1, cost);
+            assert_eq!(network.min_cost_max_flow(&'S', &'T'), None);
+            assert_eq!(network.min_cost_flow(&'S', &'T', 1), None);
+        }
+
+        // Huge costs on edges whose paths still fit
+        let mut network = FlowNetwork::new();
+        network.add_edge('S', 'A', 1, i64::MAX / 2);
+        network.add_edge('A', 'T', 1, -(i64::MAX / 2));
+        network.add_edge('S', 'T', 1, i64::MAX - 1);
+        let flow = network.min_cost_max_flow(&'S', &'T');
+        assert_eq!(flow.map(|flow| (flow.flow, flow.cost)), Some((2, i64::MAX - 1)));
+    }
+
+    #[test]
+    fn test_random_networks() {
+        let mut rng = Rng::new(5);
+        for _ in 0..30 {
+            let n = 12;
+            let mut network = FlowNetwork::new();
+            for node in 0..n {
+                network.add_node(node);
+            }
+            for _ in 0..40 {
+                let (from, to) = (rng.below(n as u64) as u32, rng.below(n as u64) as u32);
+                if from != to {
+                    network.add_edge(from, to, rng.below(10), rng.below(20) as i64);
+                }
+            }
+
+            let max = network.min_cost_max_flow(&0, &(n - 1)).unwrap();
+            assert_optimal(&network, &max, 0, n as usize - 1, true);
+            if max.flow > 1 && max.flow % 2 == 0 {
+                let half = network.min_cost_flow(&0, &(n - 1), max.flow / 2).unwrap();
+                assert_optimal(&network, &half, 0, n as usize - 1, false);
+                assert!(half.cost <= max.cost);
+            }
+        }
+    }
+}
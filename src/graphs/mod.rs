@@ -1,4 +1,21 @@
 pub mod bfs_graph;
 pub mod dfs_graph;
 pub mod dijkstras_shortest_path;
-pub mod dijkstras_shortest_path_heap;
\ No newline at end of file
+pub mod dijkstras_shortest_path_heap;
+pub mod csr_graph;
+pub mod bit_matrix;
+pub mod adjacency_matrix;
+pub mod generators;
+pub mod connected_components;
+pub mod cycles;
+pub mod centrality;
+pub mod coloring;
+pub mod tsp;
+pub mod dynamic_shortest_paths;
+pub mod implicit_search;
+#[cfg(feature = "parallel")]
+pub mod parallel_search;
+pub mod strongly_connected_components;
+pub mod transitive_closure;
+pub mod min_cost_flow;
+pub mod constrained_shortest_paths;
\ No newline at end of file
@@ -0,0 +1,285 @@
+/**
+ * Parallel Graph Search
+ *
+ * Multi-threaded versions of `CsrGraph::bfs_distances` and `CsrGraph::dijkstra_distances`, using
+ * only `std::thread::scope`. Enabled with the `parallel` cargo feature:
+ *
+ * `cargo test --features parallel`
+ *
+ * - `parallel_bfs` is a level-synchronous BFS: the whole frontier is expanded at once, split across
+ *   the threads, and nodes are claimed with an atomic compare-and-swap so each is visited once.
+ * - `direction_optimizing_bfs` switches to a bottom-up step while the frontier is large: instead of
+ *   every frontier node scanning its outgoing edges, every unvisited node scans its incoming edges
+ *   and stops at the first parent in the frontier (Beamer's algorithm).
+ * - `delta_stepping` is a parallel single source shortest path algorithm. Nodes are kept in buckets
+ *   of width `delta` by tentative distance; the lightest bucket is settled by relaxing its edges in
+ *   parallel, and only heavy edges (weight > `delta`) wait until the bucket is done.
+ *
+ * The distances are always exactly those of the sequential versions; only the order in which nodes
+ * are discovered depends on the scheduling of the threads.
+ */
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::thread;
+
+use crate::graphs::csr_graph::CsrGraph;
+
+const UNVISITED: u32 = u32::MAX;
+const UNREACHABLE: u64 = u64::MAX;
+
+// Below this many items per step the threads cost more than they save.
+const SEQUENTIAL_CUTOFF: usize = 1024;
+
+// Direction-optimizing thresholds from Beamer, Asanović and Patterson (2012).
+const ALPHA: usize = 14;
+const BETA: usize = 24;
+
+// Split `0..len` into one range per thread, run `f` on each in parallel and concatenate the results.
+fn parallel_map<R, F>(len: usize, threads: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(Range<usize>) -> Vec<R> + Sync,
+{
+    let threads = threads.max(1);
+    if threads == 1 || len < SEQUENTIAL_CUTOFF {
+        return f(0..len);
+    }
+
+    let chunk = len.div_ceil(threads);
+    thread::scope(|scope| {
+        let f = &f;
+        let handles: Vec<_> = (0..len)
+            .step_by(chunk)
+            .map(|start| scope.spawn(move || f(start..(start + chunk).min(len))))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+fn into_distances(distances: Vec<AtomicU32>) -> Vec<Option<u32>> {
+    distances
+        .into_iter()
+        .map(|distance| Some(distance.into_inner()).filter(|&distance| distance != UNVISITED))
+        .collect()
+}
+
+// Expand every frontier node's outgoing edges, returning the nodes claimed for the next level.
+fn top_down_step<N: Sync>(graph: &CsrGraph<N>, frontier: &[u32], distances: &[AtomicU32], level: u32, threads: usize) -> Vec<u32> {
+    parallel_map(frontier.len(), threads, |range| {
+        let mut next = Vec::new();
+        for &node in &frontier[range] {
+            for &neighbor in graph.neighbors(node) {
+                let claimed = distances[neighbor as usize]
+                    .compare_exchange(UNVISITED, level + 1, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok();
+                if claimed {
+                    next.push(neighbor);
+                }
+            }
+        }
+        next
+    })
+}
+
+/**
+ * Level-Synchronous Parallel Breadth First Search
+ *
+ * Time Complexity: O((V + E) / P + D) with P threads and D levels
+ * Space Complexity: O(V)
+ *
+ * @param graph The graph to search
+ * @param source The index of the start node
+ * @param threads The number of threads to use
+ * @return The number of edges on the shortest path to every node, `None` if unreachable
+ */
+pub fn parallel_bfs<N: Sync>(graph: &CsrGraph<N>, source: u32, threads: usize) -> Vec<Option<u32>> {
+    let distances: Vec<AtomicU32> = (0..graph.node_count()).map(|_| AtomicU32::new(UNVISITED)).collect();
+    distances[source as usize].store(0, Ordering::Relaxed);
+
+    let mut frontier = vec![source];
+    let mut level = 0;
+    while !frontier.is_empty() {
+        frontier = top_down_step(graph, &frontier, &distances, level, threads);
+        level += 1;
+    }
+
+    into_distances(distances)
+}
+
+/**
+ * Direction-Optimizing Parallel Breadth First Search
+ *
+ * Uses top-down steps while the frontier is small and bottom-up steps while it has more edges than
+ * 1/14 of the edges not explored yet. On low diameter graphs most nodes are found in the few middle
+ * levels, where bottom-up steps skip most of the edges.
+ *
+ * Time Complexity: O((V + E) / P + D) with P threads and D levels
+ * Space Complexity: O(V + E) for the transpose
+ *
+ * @param graph The graph to search
+ * @param transpose `graph.transpose()`, to find the incoming edges of a node
+ * @param source The index of the start node
+ * @param threads The number of threads to use
+ * @return The number of edges on the shortest path to every node, `None` if unreachable
+ */
+pub fn direction_optimizing_bfs<N: Sync>(
+    graph: &CsrGraph<N>,
+    transpose: &CsrGraph<N>,
+    source: u32,
+    threads: usize,
+) -> Vec<Option<u32>> {
+    let n = graph.node_count();
+    let distances: Vec<AtomicU32> = (0..n).map(|_| AtomicU32::new(UNVISITED)).collect();
+    distances[source as usize].store(0, Ordering::Relaxed);
+
+    let mut frontier = vec![source];
+    let mut unexplored_edges = graph.edge_count();
+    let mut bottom_up = false;
+    let mut level = 0;
+    while !frontier.is_empty() {
+        let frontier_edges: usize = frontier.iter().map(|&node| graph.neighbors(node).len()).sum();
+        unexplored_edges = unexplored_edges.saturating_sub(frontier_edges);
+        if !bottom_up && frontier_edges > unexplored_edges / ALPHA {
+            bottom_up = true;
+        } else if bottom_up && frontier.len() < n / BETA {
+            bottom_up = false;
+        }
+
+        frontier = if bottom_up {
+            // Each unvisited node is written only by the thread that owns its range.
+            parallel_map(n, threads, |range| {
+                let mut next = Vec::new();
+                for node in range {
+                    if distances[node].load(Ordering::Relaxed) != UNVISITED {
+                        continue;
+                    }
+                    let found = transpose
+                        .neighbors(node as u32)
+                        .iter()
+                        .any(|&parent| distances[parent as usize].load(Ordering::Relaxed) == level);
+                    if found {
+                        distances[node].store(level + 1, Ordering::Relaxed);
+                        next.push(node as u32);
+                    }
+                }
+                next
+            })
+        } else {
+            top_down_step(graph, &frontier, &distances, level, threads)
+        };
+        level += 1;
+    }
+
+    into_distances(distances)
+}
+
+/**
+ * Delta-Stepping Single Source Shortest Paths
+ *
+ * A bucket is settled in rounds: every node in it relaxes its light edges in parallel, and nodes
+ * that improve to a distance within the same bucket are processed again in the next round. Once the
+ * bucket stays empty, its nodes relax their heavy edges, which can only reach later buckets.
+ * Improvements are written with an atomic minimum, so concurrent relaxations of one node are safe.
+ *
+ * A `delta` of 1 behaves like Dijkstra's algorithm, a very large `delta` like Bellman-Ford; around
+ * the average edge weight is usually a good choice.
+ *
+ * Time Complexity: O((V + E) / P + L) with P threads and L rounds, in practice
+ * Space Complexity: O(V)
+ *
+ * @param graph The weighted graph to search
+ * @param source The index of the start node
+ * @param delta The width of a bucket, at least 1
+ * @param threads The number of threads to use
+ * @return The lowest total weight to every node, `None` if unreachable
+ */
+pub fn delta_stepping<N: Sync>(graph: &CsrGraph<N>, source: u32, delta: u32, threads: usize) -> Vec<Option<u64>> {
+    assert!(delta > 0, "delta must be at least 1");
+    let delta = delta as u64;
+    let distances: Vec<AtomicU64> = (0..graph.node_count()).map(|_| AtomicU64::new(UNREACHABLE)).collect();
+    distances[source as usize].store(0, Ordering::Relaxed);
+
+    // Relax the light or heavy edges of `nodes` in parallel, returning the nodes that improved.
+    let relax = |nodes: &[u32], light: bool| -> Vec<u32> {
+        parallel_map(nodes.len(), threads, |range| {
+            let mut improved = Vec::new();
+            for &node in &nodes[range] {
+                let distance = distances[node as usize].load(Ordering::Relaxed);
+                for (neighbor, weight) in graph.edges(node) {
+                    if (weight as u64 <= delta) != light {
+                        continue;
+                    }
+                    let candidate = distance + weight as u64;
+                    if distances[neighbor as usize].fetch_min(candidate, Ordering::Relaxed) > candidate {
+                        improved.push(neighbor);
+                    }
+                }
+            }
+            improved
+        })
+    };
+
+    let mut buckets: BTreeMap<u64, Vec<u32>> = BTreeMap::from([(0, vec![source])]);
+    let bucket_of = |node: u32| distances[node as usize].load(Ordering::Relaxed) / delta;
+    while let Some((&bucket, _)) = buckets.first_key_value() {
+        let mut settled = Vec::new();
+        while let Some(mut nodes) = buckets.remove(&bucket) {
+            // Drop duplicates and nodes that have since moved to an earlier bucket
+            nodes.retain(|&node| bucket_of(node) == bucket);
+            nodes.sort_unstable();
+            nodes.dedup();
+            for node in relax(&nodes, true) {
+                buckets.entry(bucket_of(node)).or_default().push(node);
+            }
+            settled.extend(nodes);
+        }
+
+        settled.sort_unstable();
+        settled.dedup();
+        for node in relax(&settled, false) {
+            buckets.entry(bucket_of(node)).or_default().push(node);
+        }
+    }
+
+    distances
+        .into_iter()
+        .map(|distance| Some(distance.into_inner()).filter(|&distance| distance != UNREACHABLE))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::{self, GeneratorConfig, Weights};
+
+    #[test]
+    fn test_parallel_bfs() {
+        for (seed, directed) in [(1, false), (2, true)] {
+            let config = GeneratorConfig { seed, directed, ..GeneratorConfig::default() };
+            // Dense enough for the bottom-up steps, sparse enough to leave some nodes unreachable
+            let graph = CsrGraph::from_weighted(&generators::erdos_renyi(20_000, 0.0002, &config));
+            let transpose = graph.transpose();
+            let source = graph.index_of(&0).unwrap();
+            let expected = graph.bfs_distances(source);
+            assert!(expected.contains(&None));
+
+            for threads in [1, 2, 4, 7] {
+                assert_eq!(parallel_bfs(&graph, source, threads), expected);
+                assert_eq!(direction_optimizing_bfs(&graph, &transpose, source, threads), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_delta_stepping() {
+        let config = GeneratorConfig { seed: 3, directed: true, weights: Weights::Uniform { min: 1, max: 100 } };
+        let graph = CsrGraph::from_weighted(&generators::erdos_renyi(20_000, 0.0003, &config));
+        let source = graph.index_of(&0).unwrap();
+        let expected = graph.dijkstra_distances(source);
+
+        for (delta, threads) in [(1, 4), (20, 1), (20, 4), (50, 7), (1000, 3)] {
+            assert_eq!(delta_stepping(&graph, source, delta, threads), expected);
+        }
+    }
+}
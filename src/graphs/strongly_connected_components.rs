@@ -0,0 +1,200 @@
+/**
+ * Strongly Connected Components
+ *
+ * Splits a directed graph into the groups of nodes that can all reach each other.
+ *
+ * Tarjan's algorithm does a single depth first search. Every node gets the order in which it was
+ * discovered and a "low link": the earliest discovered node still on the stack that it can reach.
+ * A node whose low link is itself is the root of a component, which is everything above it on the
+ * stack. The search uses an explicit call stack, so long dependency chains cannot overflow the
+ * thread's stack.
+ *
+ * Components are found sinks first, i.e. in reverse topological order of the condensation: every
+ * edge between two components goes from a later component to an earlier one.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V)
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+type Graph<N> = HashMap<N, Vec<N>>;
+
+const UNVISITED: usize = usize::MAX;
+
+/**
+ * The graph of strongly connected components. Node `c` of the condensation stands for
+ * `components[c]`, and `edges[c]` lists the components it has edges to (without duplicates or self
+ * loops). Every edge goes from a higher to a lower index.
+ */
+pub struct Condensation<N> {
+    pub components: Vec<Vec<N>>,
+    pub component_of: HashMap<N, usize>,
+    pub edges: Vec<Vec<usize>>,
+}
+
+/**
+ * Find the strongly connected components of a directed graph with Tarjan's algorithm.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V)
+ *
+ * @param graph A directed graph
+ * @return The components in reverse topological order
+ */
+pub fn strongly_connected_components<N: Hash + Eq + Clone>(graph: &Graph<N>) -> Vec<Vec<N>> {
+    condensation(graph).components
+}
+
+/**
+ * Collapse every strongly connected component of a directed graph into a single node. The result is
+ * always a DAG.
+ *
+ * Time Complexity: O(V + E)
+ * Space Complexity: O(V + E)
+ */
+pub fn condensation<N: Hash + Eq + Clone>(graph: &Graph<N>) -> Condensation<N> {
+    let mut ids: Vec<N> = Vec::new();
+    let mut index: HashMap<&N, usize> = HashMap::new();
+    for node in graph.keys().chain(graph.values().flatten()) {
+        if !index.contains_key(node) {
+            index.insert(node, ids.len());
+            ids.push(node.clone());
+        }
+    }
+    let mut adjacency = vec![Vec::new(); ids.len()];
+    for (from, neighbors) in graph {
+        adjacency[index[from]].extend(neighbors.iter().map(|to| index[to]));
+    }
+
+    let component = tarjan(&adjacency);
+    let count = component.iter().map(|&c| c + 1).max().unwrap_or(0);
+    let mut components = vec![Vec::new(); count];
+    let mut edges = vec![Vec::new(); count];
+    for (node, neighbors) in adjacency.iter().enumerate() {
+        let from = component[node];
+        components[from].push(ids[node].clone());
+        for &to in neighbors {
+            if component[to] != from {
+                edges[from].push(component[to]);
+            }
+        }
+    }
+    for targets in edges.iter_mut() {
+        targets.sort_unstable();
+        targets.dedup();
+    }
+
+    let component_of = ids.into_iter().zip(component).collect();
+    Condensation { components, component_of, edges }
+}
+
+// Tarjan's algorithm on a dense graph, returning the component of every node.
+fn tarjan(adjacency: &[Vec<usize>]) -> Vec<usize> {
+    let n = adjacency.len();
+    let mut order = vec![UNVISITED; n];
+    let mut low_link = vec![0; n];
+    let mut component = vec![UNVISITED; n];
+    let mut stack = Vec::new();
+    let mut next_order = 0;
+    let mut next_component = 0;
+
+    for root in 0..n {
+        if order[root] != UNVISITED {
+            continue;
+        }
+        order[root] = next_order;
+        low_link[root] = next_order;
+        next_order += 1;
+        stack.push(root);
+        let mut calls = vec![(root, 0)];
+
+        while let Some(frame) = calls.last_mut() {
+            let (node, edge) = *frame;
+            if let Some(&next) = adjacency[node].get(edge) {
+                frame.1 += 1;
+                if order[next] == UNVISITED {
+                    order[next] = next_order;
+                    low_link[next] = next_order;
+                    next_order += 1;
+                    stack.push(next);
+                    calls.push((next, 0));
+                } else if component[next] == UNVISITED {
+                    // Still on the stack
+                    low_link[node] = low_link[node].min(order[next]);
+                }
+                continue;
+            }
+
+            calls.pop();
+            if low_link[node] == order[node] {
+                while let Some(member) = stack.pop() {
+                    component[member] = next_component;
+                    if member == node {
+                        break;
+                    }
+                }
+                next_component += 1;
+            }
+            if let Some(&(parent, _)) = calls.last() {
+                low_link[parent] = low_link[parent].min(low_link[node]);
+            }
+        }
+    }
+
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let mut graph = Graph::new();
+        graph.insert('A', vec!['B']);
+        graph.insert('B', vec!['C', 'E']);
+        graph.insert('C', vec!['A', 'D']);
+        graph.insert('D', vec!['D']);
+        graph.insert('E', vec!['F']);
+        graph.insert('F', vec!['E', 'G']);
+
+        let condensation = condensation(&graph);
+        let mut components: Vec<Vec<char>> = condensation
+            .components
+            .iter()
+            .map(|component| {
+                let mut component = component.clone();
+                component.sort();
+                component
+            })
+            .collect();
+        components.sort();
+        assert_eq!(components, vec![vec!['A', 'B', 'C'], vec!['D'], vec!['E', 'F'], vec!['G']]);
+
+        // Edges always point to earlier components
+        let of = |node: char| condensation.component_of[&node];
+        for (from, targets) in condensation.edges.iter().enumerate() {
+            assert!(targets.iter().all(|&to| to < from));
+        }
+        assert_eq!(condensation.edges[of('A')], {
+            let mut targets = vec![of('D'), of('E')];
+            targets.sort();
+            targets
+        });
+        assert_eq!(condensation.edges[of('E')], vec![of('G')]);
+        assert!(condensation.edges[of('G')].is_empty());
+    }
+
+    #[test]
+    fn test_long_chain() {
+        // Deep enough to overflow a recursive search
+        let n = 200_000u32;
+        let mut graph: Graph<u32> = (0..n).map(|i| (i, vec![i + 1])).collect();
+        graph.insert(n, vec![0]);
+        assert_eq!(strongly_connected_components(&graph).len(), 1);
+
+        graph.insert(n, vec![]);
+        assert_eq!(strongly_connected_components(&graph).len(), n as usize + 1);
+    }
+}
@@ -0,0 +1,286 @@
+/**
+ * Transitive Closure and Reduction
+ *
+ * For dependency graphs where `A -> B` means "A depends on B":
+ *
+ * - `Reachability` answers "does A depend on B, directly or indirectly?" in constant time and lists
+ *   every transitive dependency of a node. It stores one bitset row per node of a `BitMatrix`.
+ *   - `Reachability::from_dag` builds the rows of a DAG in reverse topological order, so every row is
+ *     the union of the rows of its direct dependencies.
+ *   - `Reachability::new` works on any directed graph: nodes on a cycle all reach the same nodes, so
+ *     the rows are built for the condensation (one node per strongly connected component) instead.
+ * - `transitive_reduction` removes every edge that is implied by a longer path, which gives the
+ *   smallest graph with the same reachability: the minimal dependency graph to show a user.
+ *
+ * A node only reaches itself if it is on a cycle (or has a self loop).
+ */
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::graphs::bit_matrix::BitMatrix;
+use crate::graphs::strongly_connected_components::condensation;
+
+type Graph<N> = HashMap<N, Vec<N>>;
+
+pub struct Reachability<N> {
+    components: Vec<Vec<N>>,
+    component_of: HashMap<N, usize>,
+    // Row `c` holds the components reachable from component `c`
+    closure: BitMatrix,
+}
+
+impl<N: Hash + Eq + Clone> Reachability<N> {
+    /**
+     * Build the transitive closure of any directed graph by condensing its strongly connected
+     * components.
+     *
+     * Time Complexity: O(V + E + C * E' / 64) for C components and E' edges between them
+     * Space Complexity: O(V + C^2 / 64)
+     */
+    pub fn new(graph: &Graph<N>) -> Self {
+        let condensation = condensation(graph);
+        let count = condensation.components.len();
+        let mut closure = BitMatrix::new(count, count);
+
+        // Components are numbered sinks first, so every target row is complete before it is used.
+        for component in 0..count {
+            let members = &condensation.components[component];
+            let self_loop = graph.get(&members[0]).is_some_and(|neighbors| neighbors.contains(&members[0]));
+            if members.len() > 1 || self_loop {
+                closure.set(component, component);
+            }
+            for &target in &condensation.edges[component] {
+                closure.set(component, target);
+                closure.union_rows(component, target);
+            }
+        }
+
+        Reachability { components: condensation.components, component_of: condensation.component_of, closure }
+    }
+
+    /**
+     * Build the transitive closure of a DAG directly, without looking for components.
+     *
+     * Time Complexity: O(V + V * E / 64)
+     * Space Complexity: O(V^2 / 64)
+     *
+     * @param graph A directed graph
+     * @return The closure, `None` if the graph has a cycle
+     */
+    pub fn from_dag(graph: &Graph<N>) -> Option<Self> {
+        let (nodes, adjacency) = index_graph(graph);
+        let order = topological_order(&adjacency)?;
+
+        let mut closure = BitMatrix::new(nodes.len(), nodes.len());
+        for &node in order.iter().rev() {
+            for &target in &adjacency[node] {
+                closure.set(node, target);
+                closure.union_rows(node, target);
+            }
+        }
+
+        let component_of = nodes.iter().cloned().enumerate().map(|(i, node)| (node, i)).collect();
+        let components = nodes.into_iter().map(|node| vec![node]).collect();
+        Some(Reachability { components, component_of, closure })
+    }
+
+    /**
+     * Check whether there is a path of at least one edge from `from` to `to`.
+     */
+    pub fn reaches(&self, from: &N, to: &N) -> bool {
+        match (self.component_of.get(from), self.component_of.get(to)) {
+            (Some(&from), Some(&to)) => self.closure.get(from, to),
+            _ => false,
+        }
+    }
+
+    /**
+     * Every node reachable from `node`, i.e. all of its transitive dependencies.
+     */
+    pub fn reachable_from(&self, node: &N) -> Vec<N> {
+        match self.component_of.get(node) {
+            Some(&component) => {
+                self.closure.ones(component).flat_map(|target| self.components[target].iter().cloned()).collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /**
+     * The number of nodes reachable from `node`.
+     */
+    pub fn reachable_count(&self, node: &N) -> usize {
+        match self.component_of.get(node) {
+            Some(&component) => self.closure.ones(component).map(|target| self.components[target].len()).sum(),
+            None => 0,
+        }
+    }
+
+    /**
+     * The closure as a graph with an edge from every node to every node it reaches.
+     */
+    pub fn to_graph(&self) -> Graph<N> {
+        self.component_of.keys().map(|node| (node.clone(), self.reachable_from(node))).collect()
+    }
+}
+
+/**
+ * Transitive Reduction
+ *
+ * Keeps an edge `u -> v` only if `v` cannot be reached through another direct successor of `u`.
+ * For a DAG the result is unique. Duplicate edges are merged and the order of the remaining edges
+ * is kept.
+ *
+ * Time Complexity: O(V * E / 64 + sum of out-degree^2)
+ * Space Complexity: O(V^2 / 64)
+ *
+ * @param graph A directed graph
+ * @return The reduced graph, `None` if the graph has a cycle
+ */
+pub fn transitive_reduction<N: Hash + Eq + Clone>(graph: &Graph<N>) -> Option<Graph<N>> {
+    let reachability = Reachability::from_dag(graph)?;
+    let mut reduced: Graph<N> = reachability.component_of.keys().map(|node| (node.clone(), Vec::new())).collect();
+
+    for (from, neighbors) in graph {
+        let kept = reduced.get_mut(from).unwrap();
+        for to in neighbors {
+            let implied = neighbors.iter().any(|other| other != to && reachability.reaches(other, to));
+            if !implied && !kept.contains(to) {
+                kept.push(to.clone());
+            }
+        }
+    }
+
+    Some(reduced)
+}
+
+// Number the nodes (including those that only appear as edge targets).
+fn index_graph<N: Hash + Eq + Clone>(graph: &Graph<N>) -> (Vec<N>, Vec<Vec<usize>>) {
+    let mut nodes = Vec::new();
+    let mut index: HashMap<&N, usize> = HashMap::new();
+    for node in graph.keys().chain(graph.values().flatten()) {
+        if !index.contains_key(node) {
+            index.insert(node, nodes.len());
+            nodes.push(node.clone());
+        }
+    }
+    let mut adjacency = vec![Vec::new(); nodes.len()];
+    for (from, neighbors) in graph {
+        adjacency[index[from]].extend(neighbors.iter().map(|to| index[to]));
+    }
+    (nodes, adjacency)
+}
+
+// Kahn's algorithm: `None` if some nodes never reach in-degree 0 because they are on a cycle.
+fn topological_order(adjacency: &[Vec<usize>]) -> Option<Vec<usize>> {
+    let mut in_degree = vec![0; adjacency.len()];
+    for &target in adjacency.iter().flatten() {
+        in_degree[target] += 1;
+    }
+    let mut queue: VecDeque<usize> = (0..adjacency.len()).filter(|&node| in_degree[node] == 0).collect();
+    let mut order = Vec::with_capacity(adjacency.len());
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for &target in &adjacency[node] {
+            in_degree[target] -= 1;
+            if in_degree[target] == 0 {
+                queue.push_back(target);
+            }
+        }
+    }
+    (order.len() == adjacency.len()).then_some(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::{self, to_adjacency_lists, GeneratorConfig};
+
+    fn create_dependency_graph() -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        graph.insert("app", vec!["http", "json", "log"]);
+        graph.insert("http", vec!["tls", "log", "io"]);
+        graph.insert("tls", vec!["io", "crypto"]);
+        graph.insert("json", vec!["io"]);
+        graph.insert("log", vec!["io"]);
+        graph
+    }
+
+    fn sorted(mut nodes: Vec<&'static str>) -> Vec<&'static str> {
+        nodes.sort();
+        nodes
+    }
+
+    #[test]
+    fn test_dag_closure_and_reduction() {
+        let graph = create_dependency_graph();
+        let closure = Reachability::from_dag(&graph).unwrap();
+        assert!(closure.reaches(&"app", &"crypto"));
+        assert!(!closure.reaches(&"json", &"log"));
+        assert!(!closure.reaches(&"app", &"app"));
+        assert_eq!(sorted(closure.reachable_from(&"http")), vec!["crypto", "io", "log", "tls"]);
+        assert_eq!(closure.reachable_count(&"app"), 6);
+        assert!(closure.reachable_from(&"crypto").is_empty());
+
+        let reduced = transitive_reduction(&graph).unwrap();
+        assert_eq!(reduced[&"app"], vec!["http", "json"]);
+        assert_eq!(reduced[&"http"], vec!["tls", "log"]);
+        assert_eq!(reduced[&"tls"], vec!["io", "crypto"]);
+        assert!(reduced[&"io"].is_empty());
+
+        let mut cyclic = graph.clone();
+        cyclic.insert("io", vec!["app"]);
+        assert!(Reachability::from_dag(&cyclic).is_none());
+        assert_eq!(transitive_reduction(&cyclic), None);
+    }
+
+    #[test]
+    fn test_condensed_closure() {
+        let mut graph = create_dependency_graph();
+        graph.insert("io", vec!["tls"]);
+        graph.insert("crypto", vec!["crypto"]);
+        let closure = Reachability::new(&graph);
+
+        // io and tls are on a cycle, so each reaches itself
+        assert!(closure.reaches(&"io", &"io"));
+        assert!(closure.reaches(&"io", &"crypto"));
+        assert!(closure.reaches(&"crypto", &"crypto"));
+        assert!(!closure.reaches(&"app", &"app"));
+        assert_eq!(sorted(closure.reachable_from(&"log")), vec!["crypto", "io", "tls"]);
+        assert_eq!(closure.to_graph().len(), 7);
+    }
+
+    #[test]
+    fn test_matches_adjacency_matrix() {
+        use crate::graphs::adjacency_matrix::AdjacencyMatrix;
+
+        for (seed, directed) in [(1, true), (2, false)] {
+            let config = GeneratorConfig { seed, directed, ..GeneratorConfig::default() };
+            let graph = to_adjacency_lists(&generators::erdos_renyi(120, 0.015, &config));
+            let matrix = AdjacencyMatrix::from(&graph);
+            let expected = matrix.transitive_closure();
+            let closure = Reachability::new(&graph);
+            for from in 0..120 {
+                for to in 0..120 {
+                    let (i, j) = (matrix.index_of(&from).unwrap(), matrix.index_of(&to).unwrap());
+                    assert_eq!(closure.reaches(&from, &to), expected.get(i, j));
+                }
+            }
+        }
+
+        // On a DAG both constructions agree, and the reduction keeps the same reachability
+        let dag = to_adjacency_lists(&generators::random_dag(80, 0.1, &GeneratorConfig::default()));
+        let general = Reachability::new(&dag);
+        let direct = Reachability::from_dag(&dag).unwrap();
+        let reduced = Reachability::new(&transitive_reduction(&dag).unwrap());
+        for from in 0..80 {
+            let mut expected = general.reachable_from(&from);
+            expected.sort();
+            for closure in [&direct, &reduced] {
+                let mut actual = closure.reachable_from(&from);
+                actual.sort();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,547 @@
+/**
+ * Traveling Salesman Problem
+ *
+ * Find the cheapest tour that visits every node exactly once and returns to the start.
+ *
+ * The TSP is NP-hard. `held_karp` finds an optimal tour for small graphs (at most 24 nodes);
+ * larger instances use a construction heuristic followed by local improvement:
+ * - `nearest_neighbor` always travels to the closest unvisited node.
+ * - `christofides` shortcuts an Euler tour of a minimum spanning tree plus a matching of its odd
+ *   degree nodes.
+ * - `two_opt` and `or_opt` repeatedly apply small changes to a tour while they make it cheaper.
+ *
+ * The graphs are the weighted maps used by `dijkstras_shortest_path_heap` and are treated as
+ * undirected: an edge listed in only one direction can be used both ways, and if both directions
+ * are listed the lighter one is used. A tour can only use existing edges, so the functions return
+ * `None` when they cannot build one.
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+type Graph<N> = HashMap<N, HashMap<N, u32>>;
+
+const MISSING: u64 = u64::MAX;
+
+/**
+ * A closed tour. `nodes` lists every node once, starting with the first; the edge from the last node
+ * back to the first is included in `cost`.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tour<N> {
+    pub nodes: Vec<N>,
+    pub cost: u64,
+}
+
+// Symmetric distance matrix with the nodes numbered in sorted order.
+struct Distances<N> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    distances: Vec<u64>,
+}
+
+impl<N: Hash + Eq + Clone + Ord> Distances<N> {
+    fn new(graph: &Graph<N>) -> Self {
+        let mut nodes: Vec<N> = graph.keys().chain(graph.values().flat_map(|n| n.keys())).cloned().collect();
+        nodes.sort();
+        nodes.dedup();
+        let index: HashMap<N, usize> = nodes.iter().cloned().enumerate().map(|(i, node)| (node, i)).collect();
+
+        let n = nodes.len();
+        let mut distances = vec![MISSING; n * n];
+        for (from, neighbors) in graph {
+            for (to, &weight) in neighbors {
+                let (a, b) = (index[from], index[to]);
+                let weight = (weight as u64).min(distances[a * n + b]);
+                distances[a * n + b] = weight;
+                distances[b * n + a] = weight;
+            }
+        }
+
+        Distances { nodes, index, distances }
+    }
+
+    fn to_tour(&self, tour: &[usize]) -> Option<Tour<N>> {
+        let cost = self.cost(tour)?;
+        Some(Tour { nodes: tour.iter().map(|&node| self.nodes[node].clone()).collect(), cost })
+    }
+
+    // The tour as node numbers, `None` if it has a node that is not in the graph.
+    fn indices(&self, tour: &Tour<N>) -> Option<Vec<usize>> {
+        tour.nodes.iter().map(|node| self.index.get(node).copied()).collect()
+    }
+}
+
+impl<N> Distances<N> {
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn get(&self, a: usize, b: usize) -> u64 {
+        self.distances[a * self.nodes.len() + b]
+    }
+
+    // Cost of a closed tour, `None` if it uses a missing edge.
+    fn cost(&self, tour: &[usize]) -> Option<u64> {
+        if tour.len() == 1 {
+            return Some(0);
+        }
+        let mut total = 0;
+        for i in 0..tour.len() {
+            let weight = self.get(tour[i], tour[(i + 1) % tour.len()]);
+            if weight == MISSING {
+                return None;
+            }
+            total += weight;
+        }
+        Some(total)
+    }
+
+    // Change in cost from replacing the edges `removed` with the edges `added`; `None` if an added edge is missing.
+    fn delta(&self, removed: &[(usize, usize)], added: &[(usize, usize)]) -> Option<i64> {
+        let mut delta = 0i64;
+        for &(a, b) in added {
+            let weight = self.get(a, b);
+            if weight == MISSING {
+                return None;
+            }
+            delta += weight as i64;
+        }
+        for &(a, b) in removed {
+            delta -= self.get(a, b) as i64;
+        }
+        Some(delta)
+    }
+}
+
+/**
+ * Compute the cost of a closed tour, `None` if it uses an edge that does not exist.
+ */
+pub fn tour_cost<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>, nodes: &[N]) -> Option<u64> {
+    let distances = Distances::new(graph);
+    let tour: Option<Vec<usize>> = nodes.iter().map(|node| distances.index.get(node).copied()).collect();
+    distances.cost(&tour?)
+}
+
+/**
+ * Held-Karp Algorithm
+ *
+ * `cost[S][j]` is the cheapest path that starts at the first node, visits exactly the nodes in the
+ * set `S` and ends at `j in S`. It is built from the smaller sets `S - {j}`, and the optimal tour
+ * closes the cheapest full path back to the start. Sets are bitmasks and the table has 2^V * V
+ * entries, so this panics for more than 24 nodes; around 20 already takes seconds.
+ *
+ * Dynamic Programming
+ *
+ * Time Complexity: O(2^V * V^2)
+ * Space Complexity: O(2^V * V)
+ *
+ * @param graph An undirected weighted graph
+ * @return An optimal tour, if any tour exists
+ */
+pub fn held_karp<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> Option<Tour<N>> {
+    let distances = Distances::new(graph);
+    let n = distances.len();
+    assert!(n <= 24, "Held-Karp needs O(2^V * V) memory, use a heuristic for more than 24 nodes");
+    if n <= 2 {
+        return distances.to_tour(&(0..n).collect::<Vec<_>>());
+    }
+
+    // Node 0 is the start; bit `j - 1` of a set stands for node `j`.
+    let others = n - 1;
+    let sets = 1usize << others;
+    let mut cost = vec![MISSING; sets * others];
+    let mut parent = vec![0u8; sets * others];
+    for j in 1..n {
+        cost[(1 << (j - 1)) * others + (j - 1)] = distances.get(0, j);
+    }
+
+    for set in 1..sets {
+        for last in 0..others {
+            let current = cost[set * others + last];
+            if set & (1 << last) == 0 || current == MISSING {
+                continue;
+            }
+            for next in 0..others {
+                let weight = distances.get(last + 1, next + 1);
+                if set & (1 << next) != 0 || weight == MISSING {
+                    continue;
+                }
+                let extended = set | (1 << next);
+                let candidate = current + weight;
+                if candidate < cost[extended * others + next] {
+                    cost[extended * others + next] = candidate;
+                    parent[extended * others + next] = last as u8;
+                }
+            }
+        }
+    }
+
+    let full = sets - 1;
+    let (mut last, _) = (0..others)
+        .filter(|&last| cost[full * others + last] != MISSING && distances.get(last + 1, 0) != MISSING)
+        .map(|last| (last, cost[full * others + last] + distances.get(last + 1, 0)))
+        .min_by_key(|&(_, total)| total)?;
+
+    let mut tour = Vec::with_capacity(n);
+    let mut set = full;
+    while set != 0 {
+        tour.push(last + 1);
+        let previous = parent[set * others + last] as usize;
+        set &= !(1 << last);
+        last = previous;
+    }
+    tour.push(0);
+    tour.reverse();
+    distances.to_tour(&tour)
+}
+
+/**
+ * Nearest Neighbor Heuristic
+ *
+ * Starting from `start`, always move to the closest node not visited yet. Fast, but typically about
+ * 25% worse than optimal; a good starting point for `two_opt` and `or_opt`.
+ *
+ * Greedy Algorithm
+ *
+ * Time Complexity: O(V^2)
+ * Space Complexity: O(V^2)
+ *
+ * @param graph An undirected weighted graph
+ * @param start The node the tour starts from
+ * @return A tour, unless the greedy walk gets stuck
+ */
+pub fn nearest_neighbor<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>, start: &N) -> Option<Tour<N>> {
+    let distances = Distances::new(graph);
+    let n = distances.len();
+    let mut current = *distances.index.get(start)?;
+    let mut visited = vec![false; n];
+    let mut tour = vec![current];
+    visited[current] = true;
+
+    for _ in 1..n {
+        let next = (0..n)
+            .filter(|&node| !visited[node] && distances.get(current, node) != MISSING)
+            .min_by_key(|&node| distances.get(current, node))?;
+        visited[next] = true;
+        tour.push(next);
+        current = next;
+    }
+
+    distances.to_tour(&tour)
+}
+
+/**
+ * Christofides-style MST Heuristic
+ *
+ * 1. Build a minimum spanning tree (Prim's algorithm).
+ * 2. Pair up the nodes with odd tree degree, cheapest pairs first.
+ * 3. The tree plus the pairs has only even degrees, so it has an Euler circuit (Hierholzer's algorithm).
+ * 4. Walk the circuit and skip nodes that were already visited.
+ *
+ * Christofides' algorithm uses a minimum weight perfect matching in step 2, which guarantees a tour
+ * at most 1.5 times optimal on metric graphs. The greedy matching used here is much simpler and in
+ * practice close, but loses that guarantee. Needs a complete graph for the shortcuts in step 4.
+ *
+ * Time Complexity: O(V^2 log V)
+ * Space Complexity: O(V^2)
+ *
+ * @param graph An undirected weighted graph
+ * @return A tour, if the MST and the shortcuts only use existing edges
+ */
+pub fn christofides<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>) -> Option<Tour<N>> {
+    let distances = Distances::new(graph);
+    let n = distances.len();
+    if n <= 2 {
+        return distances.to_tour(&(0..n).collect::<Vec<_>>());
+    }
+
+    // Prim's algorithm on the distance matrix
+    let mut in_tree = vec![false; n];
+    let mut best = vec![(MISSING, 0); n];
+    let mut multigraph = vec![Vec::new(); n];
+    best[0] = (0, 0);
+    for _ in 0..n {
+        let node = (0..n).filter(|&node| !in_tree[node]).min_by_key(|&node| best[node].0)?;
+        if best[node].0 == MISSING {
+            return None;
+        }
+        in_tree[node] = true;
+        if node != 0 {
+            let parent = best[node].1;
+            multigraph[node].push(parent);
+            multigraph[parent].push(node);
+        }
+        for other in 0..n {
+            if !in_tree[other] && distances.get(node, other) < best[other].0 {
+                best[other] = (distances.get(node, other), node);
+            }
+        }
+    }
+
+    // Greedy matching of the odd degree nodes
+    let odd: Vec<usize> = (0..n).filter(|&node| multigraph[node].len() % 2 == 1).collect();
+    let mut pairs: Vec<(u64, usize, usize)> = Vec::new();
+    for (i, &a) in odd.iter().enumerate() {
+        for &b in &odd[i + 1..] {
+            if distances.get(a, b) != MISSING {
+                pairs.push((distances.get(a, b), a, b));
+            }
+        }
+    }
+    pairs.sort_unstable();
+    let mut matched = vec![false; n];
+    for (_, a, b) in pairs {
+        if !matched[a] && !matched[b] {
+            matched[a] = true;
+            matched[b] = true;
+            multigraph[a].push(b);
+            multigraph[b].push(a);
+        }
+    }
+    if odd.iter().any(|&node| !matched[node]) {
+        return None;
+    }
+
+    // Hierholzer's algorithm, then shortcut repeated nodes
+    let mut circuit = Vec::new();
+    let mut stack = vec![0];
+    while let Some(&node) = stack.last() {
+        if let Some(next) = multigraph[node].pop() {
+            let back = multigraph[next].iter().position(|&other| other == node).unwrap();
+            multigraph[next].swap_remove(back);
+            stack.push(next);
+        } else {
+            circuit.push(stack.pop().unwrap());
+        }
+    }
+    let mut visited = vec![false; n];
+    let tour: Vec<usize> = circuit.into_iter().filter(|&node| !std::mem::replace(&mut visited[node], true)).collect();
+
+    distances.to_tour(&tour)
+}
+
+/**
+ * 2-opt Local Search
+ *
+ * Removes two edges `(a, b)` and `(c, d)` of the tour and reconnects it as `(a, c)` and `(b, d)`
+ * by reversing the part between them, whenever that is cheaper. Repeats until no such move helps.
+ * The result has no crossing edges on a Euclidean instance.
+ *
+ * Time Complexity: O(V^2) per pass
+ * Space Complexity: O(V^2)
+ *
+ * @param graph An undirected weighted graph
+ * @param tour The tour to improve
+ * @return A tour that is no more expensive, `None` if `tour` has a node that is not in the graph or
+ * uses a missing edge
+ */
+pub fn two_opt<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>, tour: &Tour<N>) -> Option<Tour<N>> {
+    let distances = Distances::new(graph);
+    let mut tour = distances.indices(tour)?;
+    distances.cost(&tour)?;
+    let n = tour.len();
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..n.saturating_sub(2) {
+            for j in i + 2..n {
+                // Those two edges share a node when the tour wraps around
+                if i == 0 && j == n - 1 {
+                    continue;
+                }
+                let (a, b, c, d) = (tour[i], tour[i + 1], tour[j], tour[(j + 1) % n]);
+                if let Some(delta) = distances.delta(&[(a, b), (c, d)], &[(a, c), (b, d)]) {
+                    if delta < 0 {
+                        tour[i + 1..=j].reverse();
+                        improved = true;
+                    }
+                }
+            }
+        }
+    }
+
+    // Moves only use existing edges, so the tour is still valid
+    distances.to_tour(&tour)
+}
+
+/**
+ * Or-opt Local Search
+ *
+ * Moves a segment of 1 to 3 consecutive nodes to another place in the tour, in either direction,
+ * whenever that is cheaper. Repeats until no such move helps. Complements `two_opt`, which cannot
+ * move a node without reversing a whole section.
+ *
+ * Time Complexity: O(V^2) per pass
+ * Space Complexity: O(V^2)
+ *
+ * @param graph An undirected weighted graph
+ * @param tour The tour to improve
+ * @return A tour that is no more expensive, `None` if `tour` has a node that is not in the graph or
+ * uses a missing edge
+ */
+pub fn or_opt<N: Hash + Eq + Clone + Ord>(graph: &Graph<N>, tour: &Tour<N>) -> Option<Tour<N>> {
+    let distances = Distances::new(graph);
+    let mut tour = distances.indices(tour)?;
+    distances.cost(&tour)?;
+    let n = tour.len();
+
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for length in 1..=3.min(n.saturating_sub(3)) {
+            let mut start = 0;
+            while start + length <= n {
+                let segment: Vec<usize> = tour[start..start + length].to_vec();
+                let (first, last) = (segment[0], segment[length - 1]);
+                let before = tour[(start + n - 1) % n];
+                let after = tour[(start + length) % n];
+                let mut rest: Vec<usize> = tour[..start].to_vec();
+                rest.extend_from_slice(&tour[start + length..]);
+
+                let mut best: Option<(i64, usize, bool)> = None;
+                for k in 0..rest.len() {
+                    let (a, b) = (rest[k], rest[(k + 1) % rest.len()]);
+                    if a == before {
+                        continue;
+                    }
+                    for reversed in [false, true] {
+                        let (x, y) = if reversed { (last, first) } else { (first, last) };
+                        let delta = distances.delta(
+                            &[(before, first), (last, after), (a, b)],
+                            &[(before, after), (a, x), (y, b)],
+                        );
+                        if let Some(delta) = delta {
+                            if delta < best.map_or(0, |(best, _, _)| best) {
+                                best = Some((delta, k, reversed));
+                            }
+                        }
+                    }
+                }
+
+                if let Some((_, k, reversed)) = best {
+                    let mut segment = segment;
+                    if reversed {
+                        segment.reverse();
+                    }
+                    rest.splice(k + 1..k + 1, segment);
+                    tour = rest;
+                    improved = true;
+                }
+                start += 1;
+            }
+        }
+    }
+
+    distances.to_tour(&tour)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::Rng;
+
+    // Complete graph of random points in a 1000 x 1000 square with rounded Euclidean distances.
+    fn random_points(n: u32, seed: u64) -> Graph<u32> {
+        let mut rng = Rng::new(seed);
+        let points: Vec<(f64, f64)> = (0..n).map(|_| (rng.next_f64() * 1000.0, rng.next_f64() * 1000.0)).collect();
+        let mut graph = Graph::new();
+        for a in 0..n {
+            for b in 0..n {
+                if a != b {
+                    let (pa, pb) = (points[a as usize], points[b as usize]);
+                    graph.entry(a).or_default().insert(b, (pa.0 - pb.0).hypot(pa.1 - pb.1).round() as u32);
+                }
+            }
+        }
+        graph
+    }
+
+    fn assert_valid(graph: &Graph<u32>, tour: &Tour<u32>) {
+        let mut nodes = tour.nodes.clone();
+        nodes.sort();
+        assert_eq!(nodes, (0..graph.len() as u32).collect::<Vec<_>>());
+        assert_eq!(tour_cost(graph, &tour.nodes), Some(tour.cost));
+    }
+
+    #[test]
+    fn test_held_karp() {
+        // A square with sides of 1 and diagonals of 2
+        let mut graph = Graph::new();
+        graph.insert('A', HashMap::from([('B', 1), ('C', 2), ('D', 1)]));
+        graph.insert('B', HashMap::from([('C', 1), ('D', 2)]));
+        graph.insert('C', HashMap::from([('D', 1)]));
+        let tour = held_karp(&graph).unwrap();
+        assert_eq!(tour.cost, 4);
+        assert!(tour.nodes == vec!['A', 'B', 'C', 'D'] || tour.nodes == vec!['A', 'D', 'C', 'B']);
+
+        // Without the edge C-D the only tour crosses both diagonals
+        graph.get_mut(&'C').unwrap().remove(&'D');
+        let tour = held_karp(&graph).unwrap();
+        assert_eq!(tour.cost, 6);
+
+        // A path has no tour at all
+        let mut path = Graph::new();
+        path.insert('A', HashMap::from([('B', 1)]));
+        path.insert('B', HashMap::from([('C', 1)]));
+        assert_eq!(held_karp(&path), None);
+        assert_eq!(nearest_neighbor(&path, &'A'), None);
+    }
+
+    #[test]
+    fn test_heuristics() {
+        for seed in 0..5 {
+            let graph = random_points(10, seed);
+            let optimal = held_karp(&graph).unwrap();
+            assert_valid(&graph, &optimal);
+
+            let nearest = nearest_neighbor(&graph, &0).unwrap();
+            let mst = christofides(&graph).unwrap();
+            for tour in [&nearest, &mst] {
+                assert_valid(&graph, tour);
+                assert!(tour.cost >= optimal.cost);
+
+                let improved = or_opt(&graph, &two_opt(&graph, tour).unwrap()).unwrap();
+                assert_valid(&graph, &improved);
+                assert!(improved.cost <= tour.cost);
+                assert!(improved.cost >= optimal.cost);
+            }
+        }
+    }
+
+    #[test]
+    fn test_two_opt_untangles_polygon() {
+        // Points on a circle: the optimal tour visits them in order around the circle
+        let n = 12;
+        let points: Vec<(f64, f64)> = (0..n)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / n as f64;
+                (1000.0 * angle.cos(), 1000.0 * angle.sin())
+            })
+            .collect();
+        let mut graph = Graph::new();
+        for a in 0..n {
+            for b in 0..n {
+                if a != b {
+                    let (pa, pb) = (points[a], points[b]);
+                    graph.entry(a as u32).or_default().insert(b as u32, (pa.0 - pb.0).hypot(pa.1 - pb.1).round() as u32);
+                }
+            }
+        }
+
+        let scrambled: Vec<u32> = vec![0, 5, 2, 9, 4, 11, 6, 1, 8, 3, 10, 7];
+        let tour = Tour { cost: tour_cost(&graph, &scrambled).unwrap(), nodes: scrambled };
+        let improved = two_opt(&graph, &tour).unwrap();
+        let around = tour_cost(&graph, &(0..n as u32).collect::<Vec<_>>()).unwrap();
+        assert_eq!(improved.cost, around);
+
+        // Tours with an unknown node or a missing edge are rejected instead of improved
+        let unknown = Tour { nodes: vec![0, 1, 99], cost: 0 };
+        assert_eq!(two_opt(&graph, &unknown), None);
+        assert_eq!(or_opt(&graph, &unknown), None);
+        graph.get_mut(&0).unwrap().remove(&5);
+        graph.get_mut(&5).unwrap().remove(&0);
+        assert_eq!(two_opt(&graph, &tour), None);
+        assert_eq!(or_opt(&graph, &tour), None);
+    }
+}
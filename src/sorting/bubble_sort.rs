@@ -1,18 +1,71 @@
 /** Bubble Sort Algorithm Example 
  * 
- * Time Complexity: O(n^2)
+ * Time Complexity: O(n^2), O(n) if the array is already sorted
  * Memory Complexity: O(1)
  *  
  * Bubble sort is a simple sorting algorithm with a time complexity of O(n^2).
+ * 
+ * Every pass swaps neighbours that are out of order, which moves the largest remaining element to
+ * the end. Everything after the last swap of a pass is in its final place, so the next pass stops
+ * there, and a pass without swaps ends the sort.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
+
+pub fn bubble_sort<T: Ord>(array: &mut [T]) {
+    bubble_sort_by(array, T::cmp);
+}
+
+/**
+ * Sort with a comparator, e.g. `|a, b| b.cmp(a)` for descending order.
+ *
+ * Equal elements are never swapped, so the sort is stable.
+ */
+pub fn bubble_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(array, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn bubble_sort_by_observed<T, F, O>(array: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(array, &mut compare, observer);
+}
+
+/**
+ * Sort by a key extracted from every element. The key is computed for every comparison.
  */
-pub fn bubble_sort(array: &mut [i32]) {
-    let len = array.len();
-    for i in 0..len {
-        for j in 0..len - i - 1 {
-            if array[j] > array[j + 1] {
-                array.swap(j, j + 1);
+pub fn bubble_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    bubble_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+fn sort<T, F, O>(array: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let mut end = array.len();
+    while end > 1 {
+        let mut last_swap = 0;
+        for j in 1..end {
+            if compare_at(array, j - 1, j, compare, observer) == Ordering::Greater {
+                swap_at(array, j - 1, j, observer);
+                last_swap = j;
             }
         }
+        end = last_swap;
     }
 }
 
@@ -26,4 +79,28 @@ mod tests {
         bubble_sort(&mut arr);
         assert_eq!(arr, [1, 2, 3, 4, 5]);
     }
+
+    #[test]
+    fn test_bubble_sort_by() {
+        let mut arr = ["pear", "fig", "apple", "kiwi"];
+        bubble_sort_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, ["pear", "kiwi", "fig", "apple"]);
+        // Stable: "pear" stays ahead of "kiwi"
+        bubble_sort_by_key(&mut arr, |word| word.len());
+        assert_eq!(arr, ["fig", "pear", "kiwi", "apple"]);
+    }
+
+    #[test]
+    fn test_bubble_sort_early_exit() {
+        let mut comparisons = 0;
+        let mut arr: Vec<u32> = (0..100).collect();
+        arr.swap(40, 41);
+        bubble_sort_by(&mut arr, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert_eq!(arr, (0..100).collect::<Vec<_>>());
+        // One pass to fix the swap, one shorter pass to see nothing is left to do
+        assert_eq!(comparisons, 99 + 40);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,118 @@
+/**
+ * Bucket Sort
+ *
+ * Time Complexity: O(n) on average for uniformly distributed keys, O(n^2) if they all land in one bucket
+ * Space Complexity: O(n)
+ *
+ * Splits the range between the smallest and the largest key into n equally wide buckets, puts every
+ * element into the bucket its key falls into, sorts every bucket with insertion sort and concatenates
+ * the buckets. With uniformly distributed keys every bucket holds only a few elements.
+ *
+ * Insertion sort is stable and so is the distribution, so equal keys keep their order.
+ *
+ * Keys are ordered like `f64::total_cmp`, the same as in `radix_sort`. The buckets only cover the
+ * finite keys: `-inf` and negative NaNs go to the first bucket, `inf` and positive NaNs to the last.
+ */
+use crate::sorting::counting_sort::apply_order;
+use crate::sorting::insertion_sort;
+use crate::sorting::observer::{InBuffer, SortObserver};
+
+pub fn bucket_sort(arr: &mut [f64]) {
+    bucket_sort_by_key(arr, |&x| x);
+}
+
+/**
+ * Sort by a float key extracted from every element. The key is computed once per element.
+ *
+ * @param arr The elements to sort
+ * @param key The key of an element
+ */
+pub fn bucket_sort_by_key<T, F>(arr: &mut [T], key: F)
+where
+    F: FnMut(&T) -> f64,
+{
+    sort(arr, key, &mut ());
+}
+
+/**
+ * Sort by a key and report every step to `observer`. The buckets are sorted in a scratch buffer of
+ * indices, so the comparisons of two keys within a bucket are `BufferCompare`s.
+ */
+pub fn bucket_sort_by_key_observed<T, F, O>(arr: &mut [T], key: F, observer: &mut O)
+where
+    F: FnMut(&T) -> f64,
+    O: SortObserver,
+{
+    sort(arr, key, observer);
+}
+
+fn sort<T, F, O>(arr: &mut [T], mut key: F, observer: &mut O)
+where
+    F: FnMut(&T) -> f64,
+    O: SortObserver,
+{
+    let len = arr.len();
+    let keys: Vec<f64> = arr.iter().map(&mut key).collect();
+    if len < 2 {
+        return;
+    }
+    let finite = keys.iter().copied().filter(|key| key.is_finite());
+    let min = finite.clone().fold(f64::INFINITY, f64::min);
+    let max = finite.fold(f64::NEG_INFINITY, f64::max);
+    let width = (max - min) / len as f64;
+
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); len];
+    for (i, &key) in keys.iter().enumerate() {
+        let bucket = if !key.is_finite() {
+            if key.is_sign_negative() { 0 } else { len - 1 }
+        } else if width > 0.0 {
+            // The maximum would land in bucket `len`
+            (((key - min) / width) as usize).min(len - 1)
+        } else {
+            0
+        };
+        buckets[bucket].push(i);
+    }
+
+    let mut order = Vec::with_capacity(len);
+    for bucket in buckets.iter_mut() {
+        // Positions in `order`, where the bucket ends up
+        let base = order.len();
+        let mut compare = |&a: &usize, &b: &usize| keys[a].total_cmp(&keys[b]);
+        insertion_sort::sort(bucket, &mut compare, &mut InBuffer { observer: &mut *observer, base });
+        order.append(bucket);
+    }
+    apply_order(arr, order, observer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_sort() {
+        let mut arr = [0.42, 0.32, 0.23, 0.52, 0.25, 0.47, 0.51, -3.5, 0.0, 1e9];
+        bucket_sort(&mut arr);
+        assert_eq!(arr, [-3.5, 0.0, 0.23, 0.25, 0.32, 0.42, 0.47, 0.51, 0.52, 1e9]);
+
+        let mut same = [1.5; 4];
+        bucket_sort(&mut same);
+        assert_eq!(same, [1.5; 4]);
+
+        let mut special = [1.0, f64::NAN, -f64::NAN, f64::INFINITY, -0.0, f64::NEG_INFINITY, 0.0, -1.0];
+        bucket_sort(&mut special);
+        let mut expected = special;
+        expected.sort_by(f64::total_cmp);
+        assert_eq!(special.map(f64::to_bits), expected.map(f64::to_bits));
+        let mut infinite = [f64::INFINITY, f64::NEG_INFINITY, f64::INFINITY];
+        bucket_sort(&mut infinite);
+        assert_eq!(infinite, [f64::NEG_INFINITY, f64::INFINITY, f64::INFINITY]);
+
+        // Sensor readings by value, equal readings in the order they arrived
+        let mut readings: Vec<(u32, f64)> = (0..1000).map(|i| (i, ((i * 7919) % 1000) as f64 / 40.0)).collect();
+        let mut expected = readings.clone();
+        expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+        bucket_sort_by_key(&mut readings, |reading| reading.1);
+        assert_eq!(readings, expected);
+    }
+}
@@ -0,0 +1,105 @@
+/**
+ * Cocktail Shaker Sort
+ *
+ * Time Complexity: O(n^2), O(n) if the array is already sorted
+ * Space Complexity: O(1)
+ *
+ * A bidirectional bubble sort: a forward pass moves the largest element to the end, then a backward
+ * pass moves the smallest element to the front. Small values near the end (turtles) no longer need a
+ * pass each to reach the front.
+ *
+ * Everything after the last swap of a forward pass and before the last swap of a backward pass is in
+ * its final place, so the range shrinks to the unsorted middle, and a pass without swaps ends the sort.
+ *
+ * Equal elements are never swapped, so the sort is stable.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
+
+pub fn cocktail_shaker_sort<T: Ord>(arr: &mut [T]) {
+    cocktail_shaker_sort_by(arr, T::cmp);
+}
+
+pub fn cocktail_shaker_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn cocktail_shaker_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+pub fn cocktail_shaker_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    cocktail_shaker_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    // arr[..start] and arr[end..] are in their final place
+    let (mut start, mut end) = (0, arr.len());
+    while end > start + 1 {
+        let mut last_swap = start;
+        for i in start + 1..end {
+            if compare_at(arr, i - 1, i, compare, observer) == Ordering::Greater {
+                swap_at(arr, i - 1, i, observer);
+                last_swap = i;
+            }
+        }
+        end = last_swap;
+        if end <= start + 1 {
+            break;
+        }
+
+        let mut last_swap = end;
+        for i in (start + 1..end).rev() {
+            if compare_at(arr, i - 1, i, compare, observer) == Ordering::Greater {
+                swap_at(arr, i - 1, i, observer);
+                last_swap = i;
+            }
+        }
+        start = last_swap;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cocktail_shaker_sort() {
+        let mut arr = [5, 1, 4, 2, 8, 0, 2];
+        cocktail_shaker_sort(&mut arr);
+        assert_eq!(arr, [0, 1, 2, 2, 4, 5, 8]);
+
+        // A single turtle takes one pass in each direction plus a check
+        let mut arr: Vec<u32> = (1..100).chain([0]).collect();
+        let mut comparisons = 0;
+        cocktail_shaker_sort_by(&mut arr, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert_eq!(arr, (0..100).collect::<Vec<_>>());
+        assert!(comparisons < 3 * 100);
+
+        let mut words = ["pear", "fig", "apple", "kiwi"];
+        cocktail_shaker_sort_by_key(&mut words, |word| word.len());
+        assert_eq!(words, ["fig", "pear", "kiwi", "apple"]);
+    }
+}
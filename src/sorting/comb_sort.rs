@@ -0,0 +1,86 @@
+/**
+ * Comb Sort
+ *
+ * Time Complexity: O(n^2) in the worst case, about O(n log n) in practice
+ * Space Complexity: O(1)
+ *
+ * Bubble sort is slow because small values near the end (turtles) move only one position per pass.
+ * Comb sort compares elements `gap` apart instead, starting with the length of the array and shrinking
+ * the gap by a factor of 1.3 after every pass. Once the gap is 1 it is a bubble sort, which stops after
+ * the first pass without swaps.
+ *
+ * It is not stable.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
+
+pub fn comb_sort<T: Ord>(arr: &mut [T]) {
+    comb_sort_by(arr, T::cmp);
+}
+
+pub fn comb_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn comb_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+pub fn comb_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    comb_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    let mut gap = len;
+    let mut swapped = true;
+    while gap > 1 || swapped {
+        // Shrink by 1.3
+        gap = (gap * 10 / 13).max(1);
+        swapped = false;
+        for i in gap..len {
+            if compare_at(arr, i - gap, i, compare, observer) == Ordering::Greater {
+                swap_at(arr, i - gap, i, observer);
+                swapped = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comb_sort() {
+        // Turtles: the smallest values start at the end
+        let mut arr: Vec<u32> = (0..1000).rev().collect();
+        comb_sort(&mut arr);
+        assert_eq!(arr, (0..1000).collect::<Vec<_>>());
+
+        let mut people = [("dave", 25), ("bob", 30), ("alice", 25)];
+        comb_sort_by(&mut people, |a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        assert_eq!(people, [("bob", 30), ("alice", 25), ("dave", 25)]);
+        comb_sort_by_key(&mut people, |person| person.0);
+        assert_eq!(people, [("alice", 25), ("bob", 30), ("dave", 25)]);
+    }
+}
@@ -0,0 +1,153 @@
+/**
+ * Counting Sort
+ *
+ * Time Complexity: O(n + k) for keys in 0..k
+ * Space Complexity: O(n + k)
+ *
+ * Counting sort does not compare elements. It counts how often every key occurs, turns the counts into
+ * the position where each key starts in the output (a prefix sum), and then places every element at the
+ * next free position for its key. Elements with equal keys keep their order, so the sort is stable.
+ *
+ * It only pays off when the range of keys is small compared to the number of elements, e.g. ages,
+ * grades or the bytes of a larger key (which is what radix sort does). When the keys span much more
+ * than that, the counts array would be mostly empty (or not fit in memory at all), so the keys are
+ * sorted with the LSD radix sort passes instead.
+ */
+use crate::sorting::radix_sort::{lsd_sort, RadixKey};
+use crate::sorting::observer::{swap_at, SortEvent, SortObserver};
+
+// Counts arrays up to this size are always used, larger ones only up to this many counts per element.
+const MIN_COUNTS: u64 = 1 << 16;
+const COUNTS_PER_ELEMENT: u64 = 4;
+
+/**
+ * Sort values by counting them. The counts cover every value between the minimum and the maximum,
+ * so this needs O(max - min) memory, as long as that is not much more than the number of values.
+ */
+pub fn counting_sort<K: RadixKey>(arr: &mut [K]) {
+    let keys = arr.iter().map(|key| key.radix_bits()).collect();
+    sort_keys(arr, keys, &mut ());
+}
+
+/**
+ * Sort by a small non-negative key extracted from every element. The key is computed once per
+ * element.
+ *
+ * @param arr The elements to sort
+ * @param key The key of an element, the counts array has `max key - min key + 1` entries
+ */
+pub fn counting_sort_by_key<T, F>(arr: &mut [T], key: F)
+where
+    F: FnMut(&T) -> usize,
+{
+    sort(arr, key, &mut ());
+}
+
+/**
+ * Sort by a key and report every step to `observer`. Counting sort makes no comparisons, it writes the
+ * index of every element to a scratch buffer, and the elements are moved into place with swaps at the
+ * end.
+ */
+pub fn counting_sort_by_key_observed<T, F, O>(arr: &mut [T], key: F, observer: &mut O)
+where
+    F: FnMut(&T) -> usize,
+    O: SortObserver,
+{
+    sort(arr, key, observer);
+}
+
+fn sort<T, F, O>(arr: &mut [T], mut key: F, observer: &mut O)
+where
+    F: FnMut(&T) -> usize,
+    O: SortObserver,
+{
+    let keys = arr.iter().map(|element| key(element) as u64).collect();
+    sort_keys(arr, keys, observer);
+}
+
+fn sort_keys<T, O: SortObserver>(arr: &mut [T], keys: Vec<u64>, observer: &mut O) {
+    let (min, max) = match (keys.iter().min(), keys.iter().max()) {
+        (Some(&min), Some(&max)) => (min, max),
+        _ => return,
+    };
+    if max - min >= (keys.len() as u64).saturating_mul(COUNTS_PER_ELEMENT).max(MIN_COUNTS) {
+        let mut pairs: Vec<(u64, usize)> = keys.into_iter().enumerate().map(|(i, key)| (key, i)).collect();
+        lsd_sort(&mut pairs, 8, |pair| pair.0, observer);
+        apply_order(arr, pairs.into_iter().map(|(_, i)| i).collect(), observer);
+        return;
+    }
+
+    // The span is small enough now for the keys to fit in a `usize`
+    let keys: Vec<usize> = keys.into_iter().map(|key| (key - min) as usize).collect();
+    let mut positions = vec![0; (max - min) as usize + 1];
+    for &key in &keys {
+        positions[key] += 1;
+    }
+    let mut next = 0;
+    for position in positions.iter_mut() {
+        let count = *position;
+        *position = next;
+        next += count;
+    }
+
+    // `order[i]` is the index of the element that goes to position `i`
+    let mut order = vec![0; arr.len()];
+    for (i, &key) in keys.iter().enumerate() {
+        order[positions[key]] = i;
+        observer.observe(SortEvent::BufferWrite(positions[key]));
+        positions[key] += 1;
+    }
+    apply_order(arr, order, observer);
+}
+
+/**
+ * Rearrange `arr` so that position `i` holds the element that was at `order[i]`. Follows the cycles of
+ * the permutation with swaps, so it works for any `T` in O(n) time.
+ */
+pub(crate) fn apply_order<T, O: SortObserver>(arr: &mut [T], mut order: Vec<usize>, observer: &mut O) {
+    for start in 0..arr.len() {
+        let mut current = start;
+        // A position that already holds its element points to itself
+        while order[current] != current {
+            let next = order[current];
+            order[current] = current;
+            if next == start {
+                break;
+            }
+            swap_at(arr, current, next, observer);
+            current = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting_sort() {
+        let mut arr = [3i8, -2, 7, 0, -2, 5, -128, 127];
+        counting_sort(&mut arr);
+        assert_eq!(arr, [-128, -2, -2, 0, 3, 5, 7, 127]);
+
+        // Students by grade, in the order they signed up within a grade
+        let mut students = vec![("ann", 3), ("ben", 1), ("cat", 3), ("dan", 2), ("eve", 1)];
+        counting_sort_by_key(&mut students, |student| student.1);
+        assert_eq!(students, vec![("ben", 1), ("eve", 1), ("dan", 2), ("ann", 3), ("cat", 3)]);
+
+        let mut names: Vec<String> = ["kiwi", "fig", "banana", "apple", "date"].iter().map(|s| s.to_string()).collect();
+        counting_sort_by_key(&mut names, |name| name.len());
+        assert_eq!(names, ["fig", "kiwi", "date", "apple", "banana"]);
+
+        let mut empty: [u64; 0] = [];
+        counting_sort(&mut empty);
+
+        // Keys far apart are sorted without a counts array for the whole span
+        let mut wide = [u64::MAX, 0, 1 << 40, 7, u64::MAX - 1];
+        counting_sort(&mut wide);
+        assert_eq!(wide, [0, 7, 1 << 40, u64::MAX - 1, u64::MAX]);
+        let mut ids = vec![("b", usize::MAX), ("a", 3), ("c", usize::MAX), ("d", usize::MAX / 2)];
+        counting_sort_by_key(&mut ids, |id| id.1);
+        assert_eq!(ids, vec![("a", 3), ("d", usize::MAX / 2), ("b", usize::MAX), ("c", usize::MAX)]);
+    }
+}
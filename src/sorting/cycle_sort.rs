@@ -0,0 +1,213 @@
+/**
+ * Cycle Sort
+ *
+ * Time Complexity: O(n^2)
+ * Space Complexity: O(1)
+ *
+ * Takes the element at the start of the unsorted part out of the array, counts the elements smaller
+ * than it to find its final position, and puts it there. The element that was there is now in hand
+ * and is placed the same way, until the cycle comes back around and the last element fills the gap at
+ * the start. Every element that is not in place is written exactly once, straight into its final
+ * position, and one that is already in place is never moved, so the number of writes is the minimum
+ * possible. That makes it useful where writes are expensive, e.g. flash memory.
+ *
+ * Elements equal to the one being placed are skipped over, so duplicates are handled. It is not stable.
+ *
+ * The element in hand is reported at the gap it came from, the start of the cycle.
+ */
+use std::cmp::Ordering;
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+use crate::sorting::observer::{compare_at, SortEvent, SortObserver};
+
+pub fn cycle_sort<T: Ord>(arr: &mut [T]) {
+    cycle_sort_by(arr, T::cmp);
+}
+
+pub fn cycle_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn cycle_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+pub fn cycle_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    cycle_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    for start in 0..len.saturating_sub(1) {
+        let mut position = start;
+        for i in start + 1..len {
+            if compare_at(arr, i, start, compare, observer) == Ordering::Less {
+                position += 1;
+            }
+        }
+        if position == start {
+            continue;
+        }
+
+        let arr = arr.as_mut_ptr();
+        // Safety: while the hole exists `start` is a gap and only the other positions are read or
+        // written, each element is either in the array or in hand. Dropping the hole fills the gap,
+        // also when `compare` panics.
+        unsafe {
+            let mut hole = CycleHole { item: ManuallyDrop::new(ptr::read(arr.add(start))), gap: arr.add(start) };
+            // A cycle places at most every position after `start` once. An inconsistent comparator
+            // could run past the end or never close the cycle, then the element in hand goes back
+            // to the start.
+            for _ in start + 1..len {
+                // After the elements equal to it, which are already in place
+                while position < len && compare_hand(&*arr.add(position), position, &hole.item, start, compare, observer) == Ordering::Equal {
+                    position += 1;
+                }
+                if position == len {
+                    break;
+                }
+                ptr::swap(arr.add(position), &mut *hole.item);
+                observer.observe(SortEvent::Write(position));
+
+                position = start;
+                for i in start + 1..len {
+                    if compare_hand(&*arr.add(i), i, &hole.item, start, compare, observer) == Ordering::Less {
+                        position += 1;
+                    }
+                }
+                if position == start {
+                    break;
+                }
+            }
+            drop(hole);
+            observer.observe(SortEvent::Write(start));
+        }
+    }
+}
+
+/**
+ * The element in hand while a cycle is rotated, and the gap at the start of the cycle. Dropping the
+ * hole moves the element into the gap.
+ */
+struct CycleHole<T> {
+    item: ManuallyDrop<T>,
+    gap: *mut T,
+}
+
+impl<T> Drop for CycleHole<T> {
+    fn drop(&mut self) {
+        // Safety: `gap` is the only position without an element, and the hole is dropped only once.
+        unsafe {
+            ptr::write(self.gap, ManuallyDrop::take(&mut self.item));
+        }
+    }
+}
+
+// Compare the element at position `i` with the one in hand, reported at position `start`.
+fn compare_hand<T, F, O>(element: &T, i: usize, hand: &T, start: usize, compare: &mut F, observer: &mut O) -> Ordering
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    observer.observe(SortEvent::Compare(i, start));
+    compare(element, hand)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::Rng;
+    use crate::sorting::stats::SortStats;
+
+    #[test]
+    fn test_cycle_sort() {
+        let mut arr = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        cycle_sort(&mut arr);
+        assert_eq!(arr, [1, 1, 2, 3, 3, 4, 5, 5, 5, 6, 9]);
+
+        // Two cycles of length two: positions 1 and 3, and 5 and 6
+        let mut arr = [(0, 'a'), (3, 'b'), (2, 'c'), (1, 'd'), (4, 'e'), (6, 'f'), (5, 'g')];
+        let mut moved = arr.map(|_| false);
+        cycle_sort_by(&mut arr, |a, b| a.0.cmp(&b.0));
+        for (i, element) in arr.iter().enumerate() {
+            assert_eq!(element.0, i);
+            moved[i] = element.1 != (b'a' + i as u8) as char;
+        }
+        assert_eq!(moved, [false, true, false, true, false, true, true]);
+
+        // One write for every element out of place and none for the others
+        let mut arr = [(0, 'a'), (3, 'b'), (2, 'c'), (1, 'd'), (4, 'e'), (6, 'f'), (5, 'g')];
+        let mut stats = SortStats::default();
+        cycle_sort_by_observed(&mut arr, |a, b| a.0.cmp(&b.0), &mut stats);
+        assert_eq!((stats.writes, stats.swaps), (4, 0));
+        let mut reversed: Vec<u32> = (0..100).rev().collect();
+        let mut stats = SortStats::default();
+        cycle_sort_by_observed(&mut reversed, u32::cmp, &mut stats);
+        assert_eq!(reversed, (0..100).collect::<Vec<_>>());
+        assert_eq!(stats.writes, 100);
+
+        // A panicking comparator leaves every element in the array exactly once
+        let mut names: Vec<String> = ["d", "b", "e", "a", "c"].iter().map(|name| name.to_string()).collect();
+        let mut calls = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cycle_sort_by(&mut names, |a, b| {
+                calls += 1;
+                assert!(calls < 8);
+                a.cmp(b)
+            })
+        }));
+        assert!(result.is_err());
+        names.sort();
+        assert_eq!(names, ["a", "b", "c", "d", "e"]);
+
+        // A comparator that contradicts itself stays within the array and loses no element
+        let mut names: Vec<String> = ["d", "b", "c", "a"].iter().map(|name| name.to_string()).collect();
+        let mut calls = 0;
+        let mut events = Vec::new();
+        cycle_sort_by_observed(
+            &mut names,
+            |_, _| {
+                calls += 1;
+                if calls <= 3 { Ordering::Less } else { Ordering::Equal }
+            },
+            &mut events,
+        );
+        for event in events {
+            match event {
+                SortEvent::Compare(i, j) => assert!(i < 4 && j < 4),
+                SortEvent::Write(i) => assert!(i < 4),
+                event => panic!("{:?}", event),
+            }
+        }
+        names.sort();
+        assert_eq!(names, ["a", "b", "c", "d"]);
+        let mut rng = Rng::new(3);
+        let mut names: Vec<String> = (0..50).map(|i| i.to_string()).collect();
+        cycle_sort_by(&mut names, |_, _| [Ordering::Less, Ordering::Equal, Ordering::Greater][rng.below(3) as usize]);
+        names.sort_by_key(|name| name.parse::<u32>().unwrap());
+        assert_eq!(names, (0..50).map(|i| i.to_string()).collect::<Vec<_>>());
+
+        let mut words = ["ccc", "a", "bb", "a"];
+        cycle_sort_by_key(&mut words, |word| word.len());
+        assert_eq!(words, ["a", "a", "bb", "ccc"]);
+    }
+}
@@ -0,0 +1,470 @@
+/**
+ * External Merge Sort
+ *
+ * Sorts inputs that do not fit in memory, e.g. multi-gigabyte log files:
+ *
+ * 1. Records are read from the input until their estimated size reaches the memory budget. That chunk
+ *    is sorted in memory with `merge_sort_by` and written to a temporary file, a sorted run.
+ * 2. The runs are merged with a k-way merge: a min-heap holds the next record of every run, the
+ *    smallest is returned and replaced by the next record of its run.
+ *
+ * A merge keeps a file open per run, so at most `max_fan_in` runs are merged at once. While there are
+ * more runs than that, groups of them are merged into longer runs on disk first.
+ *
+ * The output is a streaming iterator, so only one record per run is in memory while merging. If the
+ * whole input fits in the budget nothing is written to disk. Every temporary file is removed as soon
+ * as its run is exhausted, or when the iterator is dropped.
+ *
+ * How records are read and written is up to a `RecordCodec`; `LineCodec` handles text with one
+ * record per line.
+ *
+ * Time Complexity: O(n log n) comparisons, every record is written to disk and read back once per
+ * merge pass, and there are O(log(runs) / log(max_fan_in)) of them
+ * Space Complexity: O(memory budget + number of runs)
+ *
+ * The sort is stable: records that compare equal come out in input order.
+ */
+use std::cmp::Ordering;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::mem::size_of;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::{iter, vec};
+
+use crate::sorting::merge_sort::merge_sort_by;
+
+/**
+ * Reads and writes the records of an external sort, both for the input and the temporary runs.
+ */
+pub trait RecordCodec {
+    type Record;
+
+    /**
+     * Read the next record, or `None` at the end of the input.
+     */
+    fn decode<R: BufRead>(&self, input: &mut R) -> io::Result<Option<Self::Record>>;
+
+    fn encode<W: Write>(&self, record: &Self::Record, output: &mut W) -> io::Result<()>;
+
+    /**
+     * An estimate of the memory a record takes, counted against the memory budget.
+     */
+    fn memory_size(&self, _record: &Self::Record) -> usize {
+        size_of::<Self::Record>()
+    }
+}
+
+/**
+ * One `String` record per line. The line breaks are not part of the records, so records must not
+ * contain `'\n'`.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LineCodec;
+
+impl RecordCodec for LineCodec {
+    type Record = String;
+
+    fn decode<R: BufRead>(&self, input: &mut R) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.ends_with('\n') {
+            line.pop();
+        }
+        Ok(Some(line))
+    }
+
+    fn encode<W: Write>(&self, record: &String, output: &mut W) -> io::Result<()> {
+        output.write_all(record.as_bytes())?;
+        output.write_all(b"\n")
+    }
+
+    fn memory_size(&self, record: &String) -> usize {
+        size_of::<String>() + record.capacity()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExternalSortConfig {
+    /// The estimated memory the records of a run may take, in bytes.
+    pub memory_budget: usize,
+    /// Where the runs are written.
+    pub temp_dir: PathBuf,
+    /// The most runs merged at once, and so the most files open at once (plus the one a merge pass
+    /// writes to). Values below 2 are treated as 2.
+    pub max_fan_in: usize,
+}
+
+impl Default for ExternalSortConfig {
+    fn default() -> Self {
+        ExternalSortConfig { memory_budget: 64 << 20, temp_dir: std::env::temp_dir(), max_fan_in: 64 }
+    }
+}
+
+/**
+ * The comparator of `external_sort`, the natural order of the records.
+ */
+pub type NaturalOrder<R> = fn(&R, &R) -> Ordering;
+
+pub fn external_sort<C, R>(input: R, codec: C, config: &ExternalSortConfig) -> io::Result<SortedRecords<C, NaturalOrder<C::Record>>>
+where
+    C: RecordCodec,
+    C::Record: Ord,
+    R: Read,
+{
+    external_sort_by(input, codec, config, C::Record::cmp)
+}
+
+/**
+ * Sort the records read from `input` with a comparator.
+ *
+ * @return The sorted records, read lazily from the runs. Reading a run can fail, so every item is
+ *         an `io::Result`. After an error the merge cannot go on, so every further item is an error
+ *         of the same kind.
+ */
+pub fn external_sort_by<C, R, F>(input: R, codec: C, config: &ExternalSortConfig, mut compare: F) -> io::Result<SortedRecords<C, F>>
+where
+    C: RecordCodec,
+    R: Read,
+    F: FnMut(&C::Record, &C::Record) -> Ordering,
+{
+    let mut input = BufReader::new(input);
+    let mut runs = Vec::new();
+    let mut chunk = Vec::new();
+    let mut chunk_size = 0;
+    while let Some(record) = codec.decode(&mut input)? {
+        chunk_size += codec.memory_size(&record);
+        chunk.push(record);
+        if chunk_size >= config.memory_budget {
+            merge_sort_by(&mut chunk, &mut compare);
+            runs.push(Run::write(&codec, config, chunk.drain(..).map(Ok))?);
+            chunk_size = 0;
+        }
+    }
+    merge_sort_by(&mut chunk, &mut compare);
+
+    if runs.is_empty() {
+        return Ok(SortedRecords { codec, compare, source: Source::Memory(chunk.into_iter()) });
+    }
+    if !chunk.is_empty() {
+        runs.push(Run::write(&codec, config, chunk.into_iter().map(Ok))?);
+    }
+
+    // Merging groups of consecutive runs keeps equal records in input order
+    let fan_in = config.max_fan_in.max(2);
+    while runs.len() > fan_in {
+        let mut merged = Vec::with_capacity(runs.len().div_ceil(fan_in));
+        let mut rest = runs.into_iter();
+        loop {
+            let mut group: Vec<Run> = rest.by_ref().take(fan_in).collect();
+            if group.len() <= 1 {
+                merged.extend(group.pop());
+                break;
+            }
+            let mut merge = Merge::new(group, &codec, &mut compare)?;
+            merged.push(Run::write(&codec, config, iter::from_fn(|| merge.pop(&codec, &mut compare)))?);
+        }
+        runs = merged;
+    }
+    let merge = Merge::new(runs, &codec, &mut compare)?;
+    Ok(SortedRecords { codec, compare, source: Source::Runs(merge) })
+}
+
+// Makes the names of the temporary files unique within the process.
+static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+
+/**
+ * A sorted run in a temporary file, which is removed when the run is dropped. The file is only open
+ * while the run is written and, from its first read on, while it is merged.
+ */
+struct Run {
+    path: PathBuf,
+    reader: Option<BufReader<File>>,
+}
+
+impl Run {
+    fn write<C: RecordCodec>(codec: &C, config: &ExternalSortConfig, records: impl Iterator<Item = io::Result<C::Record>>) -> io::Result<Run> {
+        let id = NEXT_RUN.fetch_add(1, AtomicOrdering::Relaxed);
+        let path = config.temp_dir.join(format!("external-sort-{}-{}.run", std::process::id(), id));
+        let file = OpenOptions::new().write(true).create_new(true).open(&path)?;
+        // From here on the file is removed even if writing fails
+        let run = Run { path, reader: None };
+        let mut writer = BufWriter::new(file);
+        for record in records {
+            codec.encode(&record?, &mut writer)?;
+        }
+        writer.flush()?;
+        Ok(run)
+    }
+
+    fn next<C: RecordCodec>(&mut self, codec: &C) -> io::Result<Option<C::Record>> {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => self.reader.insert(BufReader::new(File::open(&self.path)?)),
+        };
+        codec.decode(reader)
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/**
+ * The k-way merge of the runs. `heads[i]` is the next record of run `i`, and `heap` is a binary
+ * min-heap of the runs that have one, ordered by their head and then by run, so that equal records
+ * come out in input order. Exhausted runs are dropped, which removes their files.
+ *
+ * A failed read leaves a run at an unknown position, so the merge stops there and remembers the
+ * kind of the error to return it again.
+ */
+struct Merge<R> {
+    runs: Vec<Option<Run>>,
+    heads: Vec<Option<R>>,
+    heap: Vec<usize>,
+    failed: Option<io::ErrorKind>,
+}
+
+impl<R> Merge<R> {
+    fn new<C, F>(runs: Vec<Run>, codec: &C, compare: &mut F) -> io::Result<Merge<R>>
+    where
+        C: RecordCodec<Record = R>,
+        F: FnMut(&R, &R) -> Ordering,
+    {
+        let mut merge = Merge { runs: runs.into_iter().map(Some).collect(), heads: Vec::new(), heap: Vec::new(), failed: None };
+        for i in 0..merge.runs.len() {
+            let head = merge.next(i, codec)?;
+            merge.heads.push(head);
+            if merge.heads[i].is_some() {
+                merge.heap.push(i);
+                merge.sift_up(merge.heap.len() - 1, compare);
+            }
+        }
+        Ok(merge)
+    }
+
+    fn next<C: RecordCodec<Record = R>>(&mut self, run: usize, codec: &C) -> io::Result<Option<R>> {
+        match &mut self.runs[run] {
+            Some(reader) => reader.next(codec),
+            None => Ok(None),
+        }
+    }
+
+    fn less<F: FnMut(&R, &R) -> Ordering>(&self, a: usize, b: usize, compare: &mut F) -> bool {
+        let (run_a, run_b) = (self.heap[a], self.heap[b]);
+        match (&self.heads[run_a], &self.heads[run_b]) {
+            (Some(x), Some(y)) => compare(x, y).then(run_a.cmp(&run_b)) == Ordering::Less,
+            _ => unreachable!("every run on the heap has a head"),
+        }
+    }
+
+    fn sift_up<F: FnMut(&R, &R) -> Ordering>(&mut self, mut i: usize, compare: &mut F) {
+        while i > 0 && self.less(i, (i - 1) / 2, compare) {
+            self.heap.swap(i, (i - 1) / 2);
+            i = (i - 1) / 2;
+        }
+    }
+
+    fn sift_down<F: FnMut(&R, &R) -> Ordering>(&mut self, mut i: usize, compare: &mut F) {
+        loop {
+            let mut smallest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < self.heap.len() && self.less(child, smallest, compare) {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                return;
+            }
+            self.heap.swap(i, smallest);
+            i = smallest;
+        }
+    }
+
+    fn pop<C, F>(&mut self, codec: &C, compare: &mut F) -> Option<io::Result<R>>
+    where
+        C: RecordCodec<Record = R>,
+        F: FnMut(&R, &R) -> Ordering,
+    {
+        if let Some(kind) = self.failed {
+            return Some(Err(io::Error::new(kind, "an earlier read of a run failed")));
+        }
+        let &run = self.heap.first()?;
+        let record = self.heads[run].take().unwrap();
+        match self.next(run, codec) {
+            Ok(Some(next)) => self.heads[run] = Some(next),
+            Ok(None) => {
+                let last = self.heap.pop().unwrap();
+                if !self.heap.is_empty() {
+                    self.heap[0] = last;
+                }
+                self.runs[run] = None;
+            }
+            Err(error) => {
+                // Closes the files of the runs
+                self.heap.clear();
+                self.runs.clear();
+                self.failed = Some(error.kind());
+                return Some(Err(error));
+            }
+        }
+        self.sift_down(0, compare);
+        Some(Ok(record))
+    }
+}
+
+enum Source<R> {
+    Memory(vec::IntoIter<R>),
+    Runs(Merge<R>),
+}
+
+/**
+ * The sorted records of an external sort. Dropping it removes the temporary files that are left.
+ */
+pub struct SortedRecords<C: RecordCodec, F> {
+    codec: C,
+    compare: F,
+    source: Source<C::Record>,
+}
+
+impl<C, F> Iterator for SortedRecords<C, F>
+where
+    C: RecordCodec,
+    F: FnMut(&C::Record, &C::Record) -> Ordering,
+{
+    type Item = io::Result<C::Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.source {
+            Source::Memory(records) => records.next().map(Ok),
+            Source::Runs(merge) => merge.pop(&self.codec, &mut self.compare),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::Rng;
+    use std::cell::Cell;
+    use std::path::Path;
+
+    // A directory of its own per test, so the leftover files can be counted
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("external-sort-test-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_count(dir: &Path) -> usize {
+        fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn test_external_sort() {
+        let dir = temp_dir("lines");
+        let mut rng = Rng::new(7);
+        let lines: Vec<String> = (0..5000).map(|_| format!("{:x}", rng.below(100_000))).collect();
+        let input = lines.join("\n") + "\n";
+        let config = ExternalSortConfig { memory_budget: 4096, temp_dir: dir.clone(), max_fan_in: 1000 };
+
+        let mut sorted = external_sort(input.as_bytes(), LineCodec, &config).unwrap();
+        let first = sorted.next().unwrap().unwrap();
+        assert!(file_count(&dir) > 10);
+        let mut actual = vec![first];
+        actual.extend(sorted.map(Result::unwrap));
+        let mut expected = lines.clone();
+        expected.sort();
+        assert_eq!(actual, expected);
+        assert_eq!(file_count(&dir), 0);
+
+        // Dropping the iterator early removes the runs as well
+        let mut sorted = external_sort(input.as_bytes(), LineCodec, &config).unwrap();
+        sorted.next();
+        drop(sorted);
+        assert_eq!(file_count(&dir), 0);
+        fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn test_external_sort_by() {
+        // "level id" log lines by level only, equal levels stay in input order
+        let lines: Vec<String> = (0..2000).map(|id| format!("{} {}", ["warn", "error", "info"][id * 7 % 3], id)).collect();
+        let input = lines.join("\n");
+        let level = |line: &String| line.split(' ').next().unwrap().to_string();
+        let mut expected = lines.clone();
+        expected.sort_by_key(level);
+
+        let dir = temp_dir("levels");
+        for memory_budget in [1000, 1 << 20] {
+            let config = ExternalSortConfig { memory_budget, temp_dir: dir.clone(), ..Default::default() };
+            let sorted = external_sort_by(input.as_bytes(), LineCodec, &config, |a, b| level(a).cmp(&level(b))).unwrap();
+            let actual: Vec<String> = sorted.map(Result::unwrap).collect();
+            assert_eq!(actual, expected);
+        }
+
+        // Merging the runs three at a time takes several passes, which must keep the order as well
+        let config = ExternalSortConfig { memory_budget: 1000, temp_dir: dir.clone(), max_fan_in: 3 };
+        let sorted = external_sort_by(input.as_bytes(), LineCodec, &config, |a, b| level(a).cmp(&level(b))).unwrap();
+        assert!((1..=3).contains(&file_count(&dir)));
+        let actual: Vec<String> = sorted.map(Result::unwrap).collect();
+        assert_eq!(actual, expected);
+        assert_eq!(file_count(&dir), 0);
+        fs::remove_dir(dir).unwrap();
+    }
+
+    // Fails reading the record "bad" the second time, i.e. back from its run
+    struct FailingCodec {
+        bad_reads: Cell<usize>,
+    }
+
+    impl RecordCodec for FailingCodec {
+        type Record = String;
+
+        fn decode<R: BufRead>(&self, input: &mut R) -> io::Result<Option<String>> {
+            let line = LineCodec.decode(input)?;
+            if line.as_deref() == Some("bad") {
+                self.bad_reads.set(self.bad_reads.get() + 1);
+                if self.bad_reads.get() > 1 {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "bad record"));
+                }
+            }
+            Ok(line)
+        }
+
+        fn encode<W: Write>(&self, record: &String, output: &mut W) -> io::Result<()> {
+            LineCodec.encode(record, output)
+        }
+
+        fn memory_size(&self, record: &String) -> usize {
+            LineCodec.memory_size(record)
+        }
+    }
+
+    #[test]
+    fn test_external_sort_read_error() {
+        let dir = temp_dir("errors");
+        let input: String = (0..1000).map(|i| if i == 500 { "bad\n".to_string() } else { format!("{:03}\n", i) }).collect();
+        let config = ExternalSortConfig { memory_budget: 1000, temp_dir: dir.clone(), ..Default::default() };
+
+        let mut sorted = external_sort(input.as_bytes(), FailingCodec { bad_reads: Cell::new(0) }, &config).unwrap();
+        let error = sorted.by_ref().find_map(Result::err).unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        // The merge stops at the error, but keeps reporting it instead of ending early
+        assert_eq!(file_count(&dir), 0);
+        for _ in 0..3 {
+            assert_eq!(sorted.next().unwrap().unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+
+        // With several merge passes the error comes from writing a merged run
+        let config = ExternalSortConfig { max_fan_in: 2, ..config };
+        let error = external_sort(input.as_bytes(), FailingCodec { bad_reads: Cell::new(0) }, &config).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(file_count(&dir), 0);
+        fs::remove_dir(dir).unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+/**
+ * Gnome Sort
+ *
+ * Time Complexity: O(n^2), O(n) if the array is already sorted
+ * Space Complexity: O(1)
+ *
+ * A garden gnome walks along a row of flower pots. If the pot in front of it and the one behind it are
+ * in order it steps forward, otherwise it swaps them and steps back. There is no inner loop: after
+ * carrying a pot back into place the gnome walks all the way forward again, one comparison per step.
+ * It makes the same swaps as insertion sort, but about twice the comparisons, because the walk forward
+ * compares pots that were already in order.
+ *
+ * Equal pots are never swapped, so the sort is stable.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
+
+pub fn gnome_sort<T: Ord>(arr: &mut [T]) {
+    gnome_sort_by(arr, T::cmp);
+}
+
+pub fn gnome_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn gnome_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+pub fn gnome_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    gnome_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let mut gnome = 1;
+    while gnome < arr.len() {
+        if gnome == 0 || compare_at(arr, gnome - 1, gnome, compare, observer) != Ordering::Greater {
+            gnome += 1;
+        } else {
+            swap_at(arr, gnome - 1, gnome, observer);
+            gnome -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::insertion_sort::insertion_sort_by_observed;
+    use crate::sorting::stats::SortStats;
+
+    #[test]
+    fn test_gnome_sort() {
+        let mut arr = [34, 2, 10, -9, 2];
+        gnome_sort(&mut arr);
+        assert_eq!(arr, [-9, 2, 2, 10, 34]);
+
+        let mut names = vec!["carol".to_string(), "Bob".to_string(), "alice".to_string()];
+        gnome_sort_by_key(&mut names, |name| name.to_lowercase());
+        assert_eq!(names, ["alice", "Bob", "carol"]);
+
+        // On reversed input every swap costs a comparison on the way back and another on the way
+        // forward, where insertion sort compares only on the way back
+        let mut gnome = SortStats::default();
+        gnome_sort_by_observed(&mut [5, 4, 3, 2, 1], i32::cmp, &mut gnome);
+        let mut insertion = SortStats::default();
+        insertion_sort_by_observed(&mut [5, 4, 3, 2, 1], i32::cmp, &mut insertion);
+        assert_eq!((gnome.swaps, insertion.swaps), (10, 10));
+        assert_eq!((gnome.comparisons, insertion.comparisons), (20, 10));
+    }
+}
@@ -1,44 +1,297 @@
-use std::collections::BinaryHeap;
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
 
 /**
  * Heap Sort
- * 
+ *
  * Time Complexity: O(n log n) Linearithmic Time Complexity
  * Space Complexity: O(1) Constant Space Complexity
- * 
- * Heap sort is a comparison based sorting technique based on Binary Heap data structure. 
+ *
+ * Heap sort is a comparison based sorting technique based on Binary Heap data structure.
  * It is similar to selection sort where we first find the maximum element and place the
  * maximum element at the end. We repeat the same process for remaining element.
- * 
- * Heap sort is an in-place algorithm. 
- * It does not require any extra space (space complexity O(1)
+ *
+ * Heap sort is an in-place algorithm: the max heap is kept inside the slice itself, the
+ * children of index `i` are `2i + 1` and `2i + 2`. Building the heap sifts every parent down,
+ * then the maximum is repeatedly swapped behind the heap and the new root is sifted down.
+ * It does not require any extra space (space complexity O(1)) and is not stable.
+ */
+pub fn heap_sort<T: Ord>(arr: &mut [T]) {
+    heap_sort_by(arr, T::cmp);
+}
+
+/**
+ * Sort with a comparator, e.g. `|a, b| b.cmp(a)` for descending order.
+ */
+pub fn heap_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn heap_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+/**
+ * Sort by a key extracted from every element. The key is computed for every comparison.
+ */
+pub fn heap_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    heap_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+/**
+ * Bottom-Up Heap Sort
+ *
+ * Time Complexity: O(n log n), about n log n + O(n) comparisons instead of 2n log n
+ * Space Complexity: O(1)
+ *
+ * The element swapped to the root is almost always one of the smallest, so it usually sinks
+ * back to the bottom. Instead of comparing it against both children at every level, the
+ * sift first follows the larger children down to a leaf (one comparison per level) and then
+ * climbs back up to where the element belongs. Worth it when comparisons are expensive.
+ */
+pub fn bottom_up_heap_sort<T: Ord>(arr: &mut [T]) {
+    bottom_up_heap_sort_by(arr, T::cmp);
+}
+
+pub fn bottom_up_heap_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bottom_up_sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn bottom_up_heap_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    bottom_up_sort(arr, &mut compare, observer);
+}
+
+/**
+ * D-ary Heap Sort
+ *
+ * Time Complexity: O(n d log n / log d)
+ * Space Complexity: O(1)
+ *
+ * Every node has `arity` children: `arity * i + 1` to `arity * i + arity`. A wider heap is
+ * shallower, so fewer levels are visited and the children of a node share a cache line, at
+ * the cost of more comparisons per level. An arity of 2 is the plain binary heap sort.
+ */
+pub fn d_ary_heap_sort<T: Ord>(arr: &mut [T], arity: usize) {
+    d_ary_heap_sort_by(arr, arity, T::cmp);
+}
+
+pub fn d_ary_heap_sort_by<T, F>(arr: &mut [T], arity: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    d_ary_sort(arr, arity, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn d_ary_heap_sort_by_observed<T, F, O>(arr: &mut [T], arity: usize, mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    d_ary_sort(arr, arity, &mut compare, observer);
+}
+
+/**
+ * The binary heap sort with the operations reported to `observer`, also used by the quick sort when its
+ * recursion gets too deep.
  */
-pub fn heap_sort<T: Ord>(mut vec: Vec<T>) -> Vec<T> {
-    // Create a new max heap and add all the elements from the vector to it.
-    let mut heap = BinaryHeap::new();
-    for item in vec.drain(..) {
-        heap.push(item);
+pub(crate) fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    for root in (0..len / 2).rev() {
+        sift_down(arr, root, len, compare, observer);
+    }
+    // Move the maximum behind the heap, then restore the heap on the rest
+    for end in (1..len).rev() {
+        swap_at(arr, 0, end, observer);
+        sift_down(arr, 0, end, compare, observer);
+    }
+}
+
+fn bottom_up_sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    for root in (0..len / 2).rev() {
+        sift_down_bottom_up(arr, root, len, compare, observer);
+    }
+    for end in (1..len).rev() {
+        swap_at(arr, 0, end, observer);
+        sift_down_bottom_up(arr, 0, end, compare, observer);
+    }
+}
+
+fn d_ary_sort<T, F, O>(arr: &mut [T], arity: usize, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    assert!(arity >= 2, "a heap needs at least two children per node");
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    for root in (0..=(len - 2) / arity).rev() {
+        sift_down_d_ary(arr, root, len, arity, compare, observer);
+    }
+    for end in (1..len).rev() {
+        swap_at(arr, 0, end, observer);
+        sift_down_d_ary(arr, 0, end, arity, compare, observer);
+    }
+}
+
+fn sift_down<T, F, O>(heap: &mut [T], mut root: usize, len: usize, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    loop {
+        let mut child = 2 * root + 1;
+        if child >= len {
+            return;
+        }
+        if child + 1 < len && compare_at(heap, child, child + 1, compare, observer) == Ordering::Less {
+            child += 1;
+        }
+        if compare_at(heap, root, child, compare, observer) != Ordering::Less {
+            return;
+        }
+        swap_at(heap, root, child, observer);
+        root = child;
     }
+}
 
-    // Now, retrieve the elements in sorted order.
-    let mut sorted_vec = Vec::with_capacity(heap.len());
-    while let Some(item) = heap.pop() {
-        // As it's a max heap, we push each element at the front to get the elements in ascending order.
-        sorted_vec.insert(0, item);
+fn sift_down_bottom_up<T, F, O>(heap: &mut [T], root: usize, len: usize, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    // Follow the larger children down to a leaf
+    let mut node = root;
+    loop {
+        let child = 2 * node + 1;
+        if child >= len {
+            break;
+        }
+        node = if child + 1 < len && compare_at(heap, child, child + 1, compare, observer) == Ordering::Less { child + 1 } else { child };
+    }
+    // Climb back up to the first node on the path that is not smaller than the root
+    while node > root && compare_at(heap, root, node, compare, observer) == Ordering::Greater {
+        node = (node - 1) / 2;
     }
+    // Rotate: the root moves to `node` and everything above it on the path moves up a level
+    while node > root {
+        swap_at(heap, root, node, observer);
+        node = (node - 1) / 2;
+    }
+}
 
-    sorted_vec
+fn sift_down_d_ary<T, F, O>(heap: &mut [T], mut root: usize, len: usize, arity: usize, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    loop {
+        let first = arity * root + 1;
+        if first >= len {
+            return;
+        }
+        let mut largest = first;
+        for child in first + 1..(first + arity).min(len) {
+            if compare_at(heap, largest, child, compare, observer) == Ordering::Less {
+                largest = child;
+            }
+        }
+        if compare_at(heap, root, largest, compare, observer) != Ordering::Less {
+            return;
+        }
+        swap_at(heap, root, largest, observer);
+        root = largest;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graphs::generators::Rng;
 
     #[test]
     fn test_heap_sort() {
         let mut arr = [1, 5, 2, 4, 3];
-        let sorted_arr = heap_sort(arr.to_vec());
-        arr.sort();
-        assert_eq!(sorted_arr, arr);
+        heap_sort(&mut arr);
+        assert_eq!(arr, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_heap_sort_by() {
+        let mut words = ["delta".to_string(), "alpha".to_string(), "charlie".to_string(), "bravo".to_string()];
+        heap_sort_by(&mut words, |a, b| b.cmp(a));
+        assert_eq!(words, ["delta", "charlie", "bravo", "alpha"]);
+        heap_sort_by_key(&mut words, |word| word.len());
+        assert_eq!(words[3], "charlie");
+    }
+
+    #[test]
+    fn test_heap_sort_variants() {
+        let mut rng = Rng::new(17);
+        for len in [0, 1, 2, 3, 10, 257, 1000] {
+            let arr: Vec<u64> = (0..len).map(|_| rng.below(100)).collect();
+            let mut expected = arr.clone();
+            expected.sort_unstable();
+
+            let mut actual = arr.clone();
+            bottom_up_heap_sort(&mut actual);
+            assert_eq!(actual, expected);
+            for arity in 2..6 {
+                let mut actual = arr.clone();
+                d_ary_heap_sort(&mut actual, arity);
+                assert_eq!(actual, expected, "arity {}", arity);
+            }
+        }
+
+        // Bottom-up heap sort needs fewer comparisons
+        let arr: Vec<u32> = (0..4096u32).map(|i| i.wrapping_mul(2654435761) % 4099).collect();
+        let (mut standard, mut bottom_up) = (0, 0);
+        heap_sort_by(&mut arr.clone(), |a, b| {
+            standard += 1;
+            a.cmp(b)
+        });
+        bottom_up_heap_sort_by(&mut arr.clone(), |a, b| {
+            bottom_up += 1;
+            a.cmp(b)
+        });
+        assert!(bottom_up < standard * 3 / 4, "{} vs {}", bottom_up, standard);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,174 @@
+/**
+ * Insertion Sort
+ *
+ * Time Complexity: O(n^2), O(n + inversions) in general, so O(n) if the array is already sorted
+ * Space Complexity: O(1)
+ *
+ * Builds the sorted array one element at a time: every element is moved left past the larger elements
+ * before it. An element that is not smaller than its left neighbour stays where it is after a single
+ * comparison, which makes insertion sort the method of choice for small or nearly sorted arrays.
+ *
+ * Equal elements are never moved past each other, so the sort is stable.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortEvent, SortObserver};
+
+pub fn insertion_sort<T: Ord>(arr: &mut [T]) {
+    insertion_sort_by(arr, T::cmp);
+}
+
+/**
+ * Sort with a comparator, e.g. `|a, b| b.cmp(a)` for descending order.
+ */
+pub fn insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn insertion_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+/**
+ * Sort by a key extracted from every element. The key is computed for every comparison.
+ */
+pub fn insertion_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    insertion_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+/**
+ * Binary Insertion Sort
+ *
+ * Time Complexity: O(n log n) comparisons, O(n^2) moves
+ * Space Complexity: O(1)
+ *
+ * Finds the position of every element with a binary search of the sorted part instead of a linear
+ * scan. It still has to shift the larger elements over, so it only pays off when comparisons are
+ * expensive compared to moves. A new element is first compared with its left neighbour, so an
+ * already sorted array still takes n - 1 comparisons.
+ *
+ * The element goes after all elements equal to it, so the sort is stable.
+ */
+pub fn binary_insertion_sort<T: Ord>(arr: &mut [T]) {
+    binary_insertion_sort_by(arr, T::cmp);
+}
+
+pub fn binary_insertion_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    binary_sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`. Shifting the larger elements over
+ * reports one write per element moved, including the inserted one.
+ */
+pub fn binary_insertion_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    binary_sort(arr, &mut compare, observer);
+}
+
+pub fn binary_insertion_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    binary_insertion_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+/**
+ * The insertion sort with the operations reported to `observer`, also used by the quick sort for
+ * small ranges.
+ */
+pub(crate) fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    for i in 1..arr.len() {
+        let mut j = i;
+        while j > 0 && compare_at(arr, j - 1, j, compare, observer) == Ordering::Greater {
+            swap_at(arr, j - 1, j, observer);
+            j -= 1;
+        }
+    }
+}
+
+fn binary_sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    for i in 1..arr.len() {
+        if compare_at(arr, i - 1, i, compare, observer) != Ordering::Greater {
+            continue;
+        }
+        // The first position in arr[..i - 1] whose element is greater than arr[i]
+        let (mut low, mut high) = (0, i - 1);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if compare_at(arr, mid, i, compare, observer) != Ordering::Greater {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        arr[low..=i].rotate_right(1);
+        for position in low..=i {
+            observer.observe(SortEvent::Write(position));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insertion_sort() {
+        let mut arr = [5, 2, 9, 1, 5, 6];
+        insertion_sort(&mut arr);
+        assert_eq!(arr, [1, 2, 5, 5, 6, 9]);
+
+        let mut words = ["pear", "fig", "apple", "kiwi", "plum"];
+        insertion_sort_by_key(&mut words, |word| word.len());
+        assert_eq!(words, ["fig", "pear", "kiwi", "plum", "apple"]);
+    }
+
+    #[test]
+    fn test_binary_insertion_sort() {
+        let arr: Vec<u32> = (0..500).map(|i| i * 7919 % 500 / 3).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+        let mut actual = arr.clone();
+        let mut comparisons = 0;
+        binary_insertion_sort_by(&mut actual, |a, b| {
+            comparisons += 1;
+            a.cmp(b)
+        });
+        assert_eq!(actual, expected);
+        assert!(comparisons < 500 * 10);
+
+        let mut records = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')];
+        binary_insertion_sort_by_key(&mut records, |record| record.0);
+        assert_eq!(records, [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
+}
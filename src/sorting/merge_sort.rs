@@ -1,62 +1,595 @@
 /**
  * Merge Sort Algorithm Example
- * 
+ *
  * Merge sort is a sorting algorithm that uses the divide and conquer paradigm.
- * 
+ *
  * Merge sort is a divide and conquer algorithm that was invented by John von Neumann in 1945.
- * 
+ *
  * Merge sort has a time complexity of O(n log n).
- * 
+ *
+ * Both sorts in this file are stable, work for any `T` (elements are moved, never copied or cloned) and
+ * allocate a single scratch buffer of n / 2 elements up front. Each merge moves the shorter of the two
+ * runs into the buffer and merges back into the slice: forwards if the left run is shorter, backwards
+ * otherwise.
+ *
+ * - `merge_sort` is the classic top-down merge sort. Small ranges are finished with insertion sort, and
+ *   two halves that are already in order are not merged.
+ * - `natural_merge_sort` is adaptive like TimSort: it merges the runs that are already in the input
+ *   instead of splitting it in halves, so sorted and reversed input take O(n).
  */
-pub fn merge_sort<T: Ord + Copy>(arr: &mut [T]) {
+use std::cmp::Ordering;
+use std::ptr;
+
+use crate::sorting::observer::{compare_at, swap_at, Offset, SortEvent, SortObserver};
+
+pub fn merge_sort<T: Ord>(arr: &mut [T]) {
+    merge_sort_by(arr, T::cmp);
+}
+
+/**
+ * Sort with a comparator, e.g. `|a, b| b.cmp(a)` for descending order.
+ *
+ * Merge sort is stable: elements that compare equal keep their order.
+ */
+pub fn merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut buffer = Vec::with_capacity(arr.len() / 2);
+    sort(arr, 0, &mut buffer, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`. A merge reports a write for every
+ * element it moves into the slice.
+ */
+pub fn merge_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let mut buffer = Vec::with_capacity(arr.len() / 2);
+    sort(arr, 0, &mut buffer, &mut compare, observer);
+}
+
+/**
+ * Sort by a key extracted from every element. The key is computed for every comparison.
+ */
+pub fn merge_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+/**
+ * Natural Merge Sort
+ *
+ * Time Complexity: O(n log n), O(n) if the input consists of a few runs
+ * Space Complexity: O(n)
+ *
+ * Scans the input for runs: ascending, or strictly descending ones which are reversed in place.
+ * Short runs are extended to a minimum length with binary insertion sort. The runs are kept on a
+ * stack and merged so that their lengths stay balanced (the TimSort invariants).
+ *
+ * Merging gallops: once one run wins several comparisons in a row, the merge switches to an
+ * exponential search for how many more elements it can take from that run at once. How many wins
+ * that takes adapts to the data, so merging runs that barely interleave costs O(log n) comparisons.
+ */
+pub fn natural_merge_sort<T: Ord>(arr: &mut [T]) {
+    natural_merge_sort_by(arr, T::cmp);
+}
+
+pub fn natural_merge_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    natural_sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn natural_merge_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    natural_sort(arr, &mut compare, observer);
+}
+
+pub fn natural_merge_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    natural_merge_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+// Ranges up to this length are insertion sorted
+const INSERTION_THRESHOLD: usize = 20;
+// Wins in a row before a merge starts galloping
+const MIN_GALLOP: usize = 7;
+
+/**
+ * Sort `arr`, which starts at position `base` of the whole array. The operations are reported with
+ * their positions in the whole array.
+ */
+fn sort<T, F, O>(arr: &mut [T], base: usize, buffer: &mut Vec<T>, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
     let len = arr.len();
-    if len <= 1 {
+    if len <= INSERTION_THRESHOLD {
+        insertion_sort(arr, 1, compare, &mut Offset { observer, base });
         return;
     }
     let mid = len / 2;
-    merge_sort(&mut arr[..mid]);
-    merge_sort(&mut arr[mid..]);
-    merge(arr, mid);
-}
-
-/**
- * Merge two sorted arrays into one sorted array.
- * 
- * In Rust we utilize the Generic Trait Bound syntax to specify that the type T must implement the Ord and Copy traits.
- */
-fn merge<T: Ord + Copy>(arr: &mut [T], mid: usize) {
-    let mut left = Vec::with_capacity(mid);
-    let mut right = Vec::with_capacity(arr.len() - mid);
-    left.extend_from_slice(&arr[..mid]);
-    right.extend_from_slice(&arr[mid..]);
-    let mut i = 0;
-    let mut j = 0;
-    let mut k = 0;
-    while i < left.len() && j < right.len() {
-        if left[i] <= right[j] {
-            arr[k] = left[i];
-            i += 1;
+    sort(&mut arr[..mid], base, buffer, compare, observer);
+    sort(&mut arr[mid..], base + mid, buffer, compare, observer);
+    let mut observer = Offset { observer, base };
+    if compare_at(arr, mid, mid - 1, compare, &mut observer) != Ordering::Less {
+        return;
+    }
+    // Never gallop: the halves are balanced and usually interleave
+    let mut min_gallop = usize::MAX;
+    merge(arr, mid, buffer, compare, &mut observer, &mut min_gallop);
+}
+
+fn natural_sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+    let min_run = min_run_length(len);
+    let mut buffer = Vec::with_capacity(len / 2);
+    let mut min_gallop = MIN_GALLOP;
+    // (start, length) of the runs that still have to be merged
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+
+    let mut start = 0;
+    while start < len {
+        let mut run = find_run(&mut arr[start..], compare, &mut Offset { observer: &mut *observer, base: start });
+        if run < min_run {
+            let end = len.min(start + min_run);
+            insertion_sort(&mut arr[start..end], run, compare, &mut Offset { observer: &mut *observer, base: start });
+            run = end - start;
+        }
+        runs.push((start, run));
+        start += run;
+
+        while let Some(i) = next_merge(&runs, start == len) {
+            merge_runs(arr, &mut runs, i, &mut buffer, compare, observer, &mut min_gallop);
+        }
+    }
+}
+
+/**
+ * A minimum run length between 32 and 64 such that n / min_run is a power of two or just below one,
+ * which keeps the final merges balanced.
+ */
+fn min_run_length(mut len: usize) -> usize {
+    let mut remainder = 0;
+    while len >= 64 {
+        remainder |= len & 1;
+        len >>= 1;
+    }
+    len + remainder
+}
+
+/**
+ * The length of the run at the start of `arr`. A strictly descending run is reversed, so the run
+ * is ascending afterwards (strictly, so reversing it keeps the sort stable).
+ */
+fn find_run<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    if len < 2 {
+        return len;
+    }
+    let mut end = 2;
+    if compare_at(arr, 1, 0, compare, observer) == Ordering::Less {
+        while end < len && compare_at(arr, end, end - 1, compare, observer) == Ordering::Less {
+            end += 1;
+        }
+        for i in 0..end / 2 {
+            swap_at(arr, i, end - 1 - i, observer);
+        }
+    } else {
+        while end < len && compare_at(arr, end, end - 1, compare, observer) != Ordering::Less {
+            end += 1;
+        }
+    }
+    end
+}
+
+/**
+ * Which two adjacent runs on the stack to merge next, if any. Between merges every run is longer
+ * than the next two combined, so the stack holds O(log n) runs. At the end everything is merged.
+ */
+fn next_merge(runs: &[(usize, usize)], finished: bool) -> Option<usize> {
+    let count = runs.len();
+    if count < 2 {
+        return None;
+    }
+    let length = |i: usize| runs[i].1;
+    let mut i = count - 2;
+    if finished
+        || (i > 0 && length(i - 1) <= length(i) + length(i + 1))
+        || (i > 1 && length(i - 2) <= length(i - 1) + length(i))
+    {
+        if i > 0 && length(i - 1) < length(i + 1) {
+            i -= 1;
+        }
+        Some(i)
+    } else if length(i) <= length(i + 1) {
+        Some(i)
+    } else {
+        None
+    }
+}
+
+/**
+ * Merge the runs `i` and `i + 1` of the stack. Elements at the start of the left run and at the
+ * end of the right run that are already in place are skipped with a gallop first.
+ */
+fn merge_runs<T, F, O>(
+    arr: &mut [T],
+    runs: &mut Vec<(usize, usize)>,
+    i: usize,
+    buffer: &mut Vec<T>,
+    compare: &mut F,
+    observer: &mut O,
+    min_gallop: &mut usize,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let (start, left) = runs[i];
+    let (_, right) = runs.remove(i + 1);
+    runs[i] = (start, left + right);
+
+    let run = &mut arr[start..start + left + right];
+    let mut offset = Offset { observer: &mut *observer, base: start };
+    let skip = gallop(left, |k| compare_at(run, k, left, compare, &mut offset) != Ordering::Greater, false);
+    let run = &mut run[skip..];
+    let mid = left - skip;
+    if mid == 0 {
+        return;
+    }
+    let mut offset = Offset { observer, base: start + skip };
+    let keep = gallop(right, |k| compare_at(run, mid + k, mid - 1, compare, &mut offset) == Ordering::Less, true);
+    merge(&mut run[..mid + keep], mid, buffer, compare, &mut offset, min_gallop);
+}
+
+/**
+ * Binary insertion sort of `arr`, of which the first `sorted` elements are already in order.
+ * Each element is placed after all elements equal to it, so the sort is stable.
+ */
+fn insertion_sort<T, F, O>(arr: &mut [T], sorted: usize, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    for i in sorted.max(1)..arr.len() {
+        let position = gallop(i, |k| compare_at(arr, k, i, compare, observer) != Ordering::Greater, false);
+        arr[position..=i].rotate_right(1);
+        write_range(observer, position, i + 1 - position);
+    }
+}
+
+/**
+ * The number of leading elements among the `len` elements for which `is_before(k)` holds, where it
+ * holds for a prefix of them. An exponential search from the start (or from the end) narrows the
+ * range down before a binary search, so the cost is logarithmic in the distance to that end.
+ */
+fn gallop<P>(len: usize, mut is_before: P, from_end: bool) -> usize
+where
+    P: FnMut(usize) -> bool,
+{
+    let (mut last, mut offset) = (0, 1);
+    let (mut low, mut high) = if from_end {
+        while offset <= len && !is_before(len - offset) {
+            last = offset;
+            offset = 2 * offset + 1;
+        }
+        (len - offset.min(len), len - last)
+    } else {
+        while offset <= len && is_before(offset - 1) {
+            last = offset;
+            offset = 2 * offset + 1;
+        }
+        (last, offset.min(len))
+    };
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if is_before(mid) {
+            low = mid + 1;
         } else {
-            arr[k] = right[j];
-            j += 1;
+            high = mid;
+        }
+    }
+    low
+}
+
+/**
+ * Merge the sorted runs `arr[..mid]` and `arr[mid..]`. The buffer must have room for the shorter
+ * one. `min_gallop` is the number of wins in a row before galloping, updated as the merge goes.
+ */
+fn merge<T, F, O>(arr: &mut [T], mid: usize, buffer: &mut Vec<T>, compare: &mut F, observer: &mut O, min_gallop: &mut usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    if mid == 0 || mid == len {
+        return;
+    }
+    assert!(buffer.is_empty() && buffer.capacity() >= mid.min(len - mid));
+    observer.observe(SortEvent::Merge { start: 0, mid, end: len });
+    if mid <= len - mid {
+        merge_forwards(arr, mid, buffer.as_mut_ptr(), compare, observer, min_gallop);
+    } else {
+        merge_backwards(arr, mid, buffer.as_mut_ptr(), compare, observer, min_gallop);
+    }
+}
+
+fn write_range<O: SortObserver>(observer: &mut O, start: usize, count: usize) {
+    for i in start..start + count {
+        observer.observe(SortEvent::Write(i));
+    }
+}
+
+/**
+ * Compare the elements at `a` and `b`, reporting their positions `i` and `j` in the slice.
+ *
+ * Safety: `a` and `b` must point to initialized elements.
+ */
+unsafe fn compare_ptr<T, F, O>(a: *const T, i: usize, b: *const T, j: usize, compare: &mut F, observer: &mut O) -> Ordering
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    observer.observe(SortEvent::Compare(i, j));
+    compare(&*a, &*b)
+}
+
+/**
+ * While merging, the elements in `start..end` of the buffer have been moved out of the slice and
+ * the gap they left starts at `dest`. Dropping the hole moves them back, so the slice owns every
+ * element exactly once again even if `compare` panics.
+ */
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        // Safety: `start..end` are initialized buffer elements and `dest` is a gap of that length.
+        unsafe {
+            let len = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, len);
         }
-        k += 1;
     }
-    while i < left.len() {
-        arr[k] = left[i];
-        i += 1;
-        k += 1;
+}
+
+// Moves the left run into the buffer and fills the slice from the front.
+fn merge_forwards<T, F, O>(arr: &mut [T], mid: usize, buffer: *mut T, compare: &mut F, observer: &mut O, min_gallop: &mut usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    let arr = arr.as_mut_ptr();
+    // Safety: the buffer has room for `mid` elements. The hole always covers the elements that are
+    // in the buffer, and `hole.dest` is exactly that many elements before `right`, so every copy
+    // moves an element into the gap and none is lost or duplicated.
+    unsafe {
+        // The position of an element in the slice, and of an element in the buffer before it was moved
+        let at = |p: *const T| p.offset_from(arr) as usize;
+        let left_at = |p: *const T| p.offset_from(buffer) as usize;
+
+        ptr::copy_nonoverlapping(arr, buffer, mid);
+        let mut hole = MergeHole { start: buffer, end: buffer.add(mid), dest: arr };
+        let mut right = arr.add(mid);
+        let right_end = arr.add(len);
+
+        'merge: loop {
+            let (mut left_wins, mut right_wins) = (0, 0);
+            while left_wins < *min_gallop && right_wins < *min_gallop {
+                if compare_ptr(right, at(right), hole.start, left_at(hole.start), compare, observer) == Ordering::Less {
+                    ptr::copy_nonoverlapping(right, hole.dest, 1);
+                    right = right.add(1);
+                    right_wins += 1;
+                    left_wins = 0;
+                } else {
+                    ptr::copy_nonoverlapping(hole.start, hole.dest, 1);
+                    hole.start = hole.start.add(1);
+                    left_wins += 1;
+                    right_wins = 0;
+                }
+                observer.observe(SortEvent::Write(at(hole.dest)));
+                hole.dest = hole.dest.add(1);
+                if hole.start == hole.end || right == right_end {
+                    break 'merge;
+                }
+            }
+
+            loop {
+                // Left elements not greater than the next right element
+                let left = hole.start;
+                let count = gallop(
+                    hole.end.offset_from(left) as usize,
+                    |k| compare_ptr(left.add(k), left_at(left.add(k)), right, at(right), compare, observer) != Ordering::Greater,
+                    false,
+                );
+                ptr::copy_nonoverlapping(hole.start, hole.dest, count);
+                write_range(observer, at(hole.dest), count);
+                hole.start = hole.start.add(count);
+                hole.dest = hole.dest.add(count);
+                if hole.start == hole.end {
+                    break 'merge;
+                }
+                ptr::copy_nonoverlapping(right, hole.dest, 1);
+                observer.observe(SortEvent::Write(at(hole.dest)));
+                right = right.add(1);
+                hole.dest = hole.dest.add(1);
+                if right == right_end {
+                    break 'merge;
+                }
+
+                // Right elements less than the next left element
+                let first_left = hole.start;
+                let right_count = gallop(
+                    right_end.offset_from(right) as usize,
+                    |k| compare_ptr(right.add(k), at(right.add(k)), first_left, left_at(first_left), compare, observer) == Ordering::Less,
+                    false,
+                );
+                ptr::copy(right, hole.dest, right_count);
+                write_range(observer, at(hole.dest), right_count);
+                right = right.add(right_count);
+                hole.dest = hole.dest.add(right_count);
+                if right == right_end {
+                    break 'merge;
+                }
+                ptr::copy_nonoverlapping(hole.start, hole.dest, 1);
+                observer.observe(SortEvent::Write(at(hole.dest)));
+                hole.start = hole.start.add(1);
+                hole.dest = hole.dest.add(1);
+                if hole.start == hole.end {
+                    break 'merge;
+                }
+
+                if count < MIN_GALLOP && right_count < MIN_GALLOP {
+                    *min_gallop = min_gallop.saturating_add(1);
+                    break;
+                }
+                *min_gallop = min_gallop.saturating_sub(1).max(1);
+            }
+        }
+        // Dropping the hole moves what is left of the left run into place
+        write_range(observer, at(hole.dest), hole.end.offset_from(hole.start) as usize);
     }
-    while j < right.len() {
-        arr[k] = right[j];
-        j += 1;
-        k += 1;
+}
+
+// Moves the right run into the buffer and fills the slice from the back.
+fn merge_backwards<T, F, O>(arr: &mut [T], mid: usize, buffer: *mut T, compare: &mut F, observer: &mut O, min_gallop: &mut usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = arr.len();
+    let arr = arr.as_mut_ptr();
+    // Safety: as in `merge_forwards`, mirrored. The left run is `arr..hole.dest` and the gap between
+    // `hole.dest` and `out` is as long as the part of the right run left in the buffer.
+    unsafe {
+        // The position of an element in the slice, and of an element in the buffer before it was moved
+        let at = |p: *const T| p.offset_from(arr) as usize;
+        let right_at = |p: *const T| mid + p.offset_from(buffer) as usize;
+
+        ptr::copy_nonoverlapping(arr.add(mid), buffer, len - mid);
+        let mut hole = MergeHole { start: buffer, end: buffer.add(len - mid), dest: arr.add(mid) };
+        let mut out = arr.add(len);
+
+        'merge: loop {
+            let (mut left_wins, mut right_wins) = (0, 0);
+            while left_wins < *min_gallop && right_wins < *min_gallop {
+                let (last_right, last_left) = (hole.end.sub(1), hole.dest.sub(1));
+                out = out.sub(1);
+                if compare_ptr(last_right, right_at(last_right), last_left, at(last_left), compare, observer) == Ordering::Less {
+                    hole.dest = hole.dest.sub(1);
+                    ptr::copy_nonoverlapping(hole.dest, out, 1);
+                    left_wins += 1;
+                    right_wins = 0;
+                } else {
+                    hole.end = hole.end.sub(1);
+                    ptr::copy_nonoverlapping(hole.end, out, 1);
+                    right_wins += 1;
+                    left_wins = 0;
+                }
+                observer.observe(SortEvent::Write(at(out)));
+                if hole.dest == arr || hole.start == hole.end {
+                    break 'merge;
+                }
+            }
+
+            loop {
+                // Right elements not less than the last left element
+                let last_left = hole.dest.sub(1);
+                let right = hole.start;
+                let right_len = hole.end.offset_from(right) as usize;
+                let count = right_len
+                    - gallop(
+                        right_len,
+                        |k| compare_ptr(right.add(k), right_at(right.add(k)), last_left, at(last_left), compare, observer) == Ordering::Less,
+                        true,
+                    );
+                out = out.sub(count);
+                hole.end = hole.end.sub(count);
+                ptr::copy_nonoverlapping(hole.end, out, count);
+                write_range(observer, at(out), count);
+                if hole.start == hole.end {
+                    break 'merge;
+                }
+                out = out.sub(1);
+                hole.dest = hole.dest.sub(1);
+                ptr::copy_nonoverlapping(hole.dest, out, 1);
+                observer.observe(SortEvent::Write(at(out)));
+                if hole.dest == arr {
+                    break 'merge;
+                }
+
+                // Left elements greater than the last right element
+                let last_right = hole.end.sub(1);
+                let left_len = hole.dest.offset_from(arr) as usize;
+                let left_count = left_len
+                    - gallop(
+                        left_len,
+                        |k| compare_ptr(arr.add(k), k, last_right, right_at(last_right), compare, observer) != Ordering::Greater,
+                        true,
+                    );
+                out = out.sub(left_count);
+                hole.dest = hole.dest.sub(left_count);
+                ptr::copy(hole.dest, out, left_count);
+                write_range(observer, at(out), left_count);
+                if hole.dest == arr {
+                    break 'merge;
+                }
+                out = out.sub(1);
+                hole.end = hole.end.sub(1);
+                ptr::copy_nonoverlapping(hole.end, out, 1);
+                observer.observe(SortEvent::Write(at(out)));
+                if hole.start == hole.end {
+                    break 'merge;
+                }
+
+                if count < MIN_GALLOP && left_count < MIN_GALLOP {
+                    *min_gallop = min_gallop.saturating_add(1);
+                    break;
+                }
+                *min_gallop = min_gallop.saturating_sub(1).max(1);
+            }
+        }
+        // Dropping the hole moves what is left of the right run into place
+        write_range(observer, at(hole.dest), hole.end.offset_from(hole.start) as usize);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graphs::generators::Rng;
 
     #[test]
     fn test_merge_sort() {
@@ -64,4 +597,87 @@ mod tests {
         merge_sort(&mut arr);
         assert_eq!(arr, [1, 2, 3, 4, 5]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_merge_sort_by() {
+        let mut scores: [(u32, f64); 4] = [(1, 0.5), (2, 0.25), (3, 0.5), (4, 0.75)];
+        merge_sort_by(&mut scores, |a, b| b.1.total_cmp(&a.1));
+        assert_eq!(scores, [(4, 0.75), (1, 0.5), (3, 0.5), (2, 0.25)]);
+        merge_sort_by_key(&mut scores, |score| score.0 % 2);
+        assert_eq!(scores, [(4, 0.75), (2, 0.25), (1, 0.5), (3, 0.5)]);
+    }
+
+    #[test]
+    fn test_stable_on_owned_values() {
+        let mut rng = Rng::new(5);
+        let mut next = move || rng.next_u64();
+        let inputs: Vec<Vec<(u64, String)>> = vec![
+            (0..1000).map(|i| (next() % 50, i.to_string())).collect(),
+            (0..1000).map(|i| (i / 10, i.to_string())).collect(),
+            (0..1000).map(|i| ((2000 - i) / 7, i.to_string())).collect(),
+            // Long runs that barely overlap, so the merges gallop
+            (0..1000).map(|i| (if i < 500 { 2 * i } else { 2 * (i - 500) + 700 }, i.to_string())).collect(),
+            (0..1000).map(|i| (if i % 100 < 90 { i } else { next() % 1000 }, i.to_string())).collect(),
+        ];
+        for input in inputs {
+            let mut expected = input.clone();
+            expected.sort_by_key(|element| element.0);
+            let mut actual = input.clone();
+            merge_sort_by_key(&mut actual, |element| element.0);
+            assert_eq!(actual, expected);
+            let mut actual = input.clone();
+            natural_merge_sort_by_key(&mut actual, |element| element.0);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_natural_merge_sort_is_adaptive() {
+        let count = |mut arr: Vec<u32>| {
+            let mut comparisons = 0;
+            natural_merge_sort_by(&mut arr, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert!(arr.windows(2).all(|pair| pair[0] <= pair[1]));
+            comparisons
+        };
+        let n = 100_000;
+        assert_eq!(count((0..n).collect()), n as usize - 1);
+        assert_eq!(count((0..n).rev().collect()), n as usize - 1);
+        // Two interleaving runs take a linear merge, two runs that do not overlap only a gallop
+        assert!(count((0..n).map(|i| (i * 2) % n + i * 2 / n).collect()) < 3 * n as usize);
+        assert!(count((n / 2..n).chain(0..n / 2).collect()) < n as usize + 100);
+    }
+
+    #[test]
+    fn test_panicking_comparator() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let input: Vec<String> = (0..500).map(|i| ((i * 7919) % 500).to_string()).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        for limit in [10, 400, 3000] {
+            for natural in [false, true] {
+                let mut arr = input.clone();
+                let mut comparisons = 0;
+                let compare = |a: &String, b: &String| {
+                    comparisons += 1;
+                    assert!(comparisons < limit, "comparator gave up");
+                    a.cmp(b)
+                };
+                let result = catch_unwind(AssertUnwindSafe(|| {
+                    if natural {
+                        natural_merge_sort_by(&mut arr, compare);
+                    } else {
+                        merge_sort_by(&mut arr, compare);
+                    }
+                }));
+                assert!(result.is_err());
+                // Every element is still there exactly once
+                arr.sort();
+                assert_eq!(arr, expected);
+            }
+        }
+    }
+}
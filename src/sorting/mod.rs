@@ -1,3 +1,20 @@
 pub mod quick_sort;
 pub mod heap_sort;
 pub mod merge_sort;
+pub mod bubble_sort;
+pub mod selection_sort;
+pub mod sorter;
+pub mod counting_sort;
+pub mod radix_sort;
+pub mod bucket_sort;
+pub mod insertion_sort;
+pub mod shell_sort;
+pub mod comb_sort;
+pub mod cocktail_shaker_sort;
+pub mod gnome_sort;
+pub mod odd_even_sort;
+pub mod cycle_sort;
+pub mod stats;
+pub mod observer;
+pub mod parallel_sort;
+pub mod external_sort;
\ No newline at end of file
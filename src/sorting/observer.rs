@@ -0,0 +1,422 @@
+/**
+ * Sort Observer
+ *
+ * Every sort in this module can report what it does, step by step, to a `SortObserver`: which
+ * positions it compares, swaps and writes, and for the divide and conquer sorts which ranges it
+ * partitions and merges. Positions are indices into the whole array being sorted, except for the
+ * `Buffer` events: the sorts by key (counting, radix and bucket sort) first sort indices or
+ * `(key, index)` pairs in a scratch buffer and report that work with positions into the buffer, and
+ * only their final swaps move the elements of the array.
+ *
+ * The plain sorts pass `()`, which ignores every event and compiles away. The `_observed` variants,
+ * e.g. `quick_sort_by_observed(&mut arr, |a, b| a.cmp(b), &mut observer)`, and
+ * `Sorter::sort_by_observed` take any observer:
+ *
+ * - `Vec<SortEvent>` records the events.
+ * - `SortStats` counts them.
+ * - `TextRenderer` draws one line per event, to watch a sort run in the terminal.
+ * - `JsonLinesExporter` writes one JSON object per event, for a frontend to animate.
+ */
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortEvent {
+    /// The elements at the two positions were compared.
+    Compare(usize, usize),
+    /// The elements at the two positions were exchanged.
+    Swap(usize, usize),
+    /// An element was stored at the position, e.g. when a merge moves it back from its buffer.
+    Write(usize),
+    /// `start..end` was partitioned: the elements equal to the pivot are now in
+    /// `pivot_start..pivot_end`, the smaller ones before and the greater ones after them.
+    Partition { start: usize, end: usize, pivot_start: usize, pivot_end: usize },
+    /// The sorted runs `start..mid` and `mid..end` are about to be merged.
+    Merge { start: usize, mid: usize, end: usize },
+    /// The entries at the two positions of a scratch buffer were compared.
+    BufferCompare(usize, usize),
+    /// An entry was stored at the position of a scratch buffer.
+    BufferWrite(usize),
+}
+
+impl SortEvent {
+    /**
+     * The same event for a subslice that starts at position `base` of the whole array. Positions in a
+     * scratch buffer stay as they are.
+     */
+    pub fn offset(self, base: usize) -> SortEvent {
+        match self {
+            SortEvent::Compare(i, j) => SortEvent::Compare(base + i, base + j),
+            SortEvent::Swap(i, j) => SortEvent::Swap(base + i, base + j),
+            SortEvent::Write(i) => SortEvent::Write(base + i),
+            SortEvent::Partition { start, end, pivot_start, pivot_end } => SortEvent::Partition {
+                start: base + start,
+                end: base + end,
+                pivot_start: base + pivot_start,
+                pivot_end: base + pivot_end,
+            },
+            SortEvent::Merge { start, mid, end } => SortEvent::Merge { start: base + start, mid: base + mid, end: base + end },
+            SortEvent::BufferCompare(..) | SortEvent::BufferWrite(..) => self,
+        }
+    }
+}
+
+pub trait SortObserver {
+    fn observe(&mut self, event: SortEvent);
+}
+
+impl SortObserver for () {
+    fn observe(&mut self, _event: SortEvent) {}
+}
+
+impl SortObserver for Vec<SortEvent> {
+    fn observe(&mut self, event: SortEvent) {
+        self.push(event);
+    }
+}
+
+impl<O: SortObserver + ?Sized> SortObserver for &mut O {
+    fn observe(&mut self, event: SortEvent) {
+        (**self).observe(event);
+    }
+}
+
+/**
+ * Reports the events of a subslice starting at `base` as events of the whole array.
+ */
+pub(crate) struct Offset<'a, O> {
+    pub observer: &'a mut O,
+    pub base: usize,
+}
+
+impl<O: SortObserver> SortObserver for Offset<'_, O> {
+    fn observe(&mut self, event: SortEvent) {
+        self.observer.observe(event.offset(self.base));
+    }
+}
+
+/**
+ * Reports the events of sorting `buffer[base..]` of a scratch buffer as `Buffer` events, e.g. to run
+ * the insertion sort on the indices of a bucket. A swap is reported as the two writes it does.
+ */
+pub(crate) struct InBuffer<'a, O> {
+    pub observer: &'a mut O,
+    pub base: usize,
+}
+
+impl<O: SortObserver> SortObserver for InBuffer<'_, O> {
+    fn observe(&mut self, event: SortEvent) {
+        match event {
+            SortEvent::Compare(i, j) => self.observer.observe(SortEvent::BufferCompare(self.base + i, self.base + j)),
+            SortEvent::Swap(i, j) => {
+                self.observer.observe(SortEvent::BufferWrite(self.base + i));
+                self.observer.observe(SortEvent::BufferWrite(self.base + j));
+            }
+            SortEvent::Write(i) => self.observer.observe(SortEvent::BufferWrite(self.base + i)),
+            SortEvent::Partition { .. } | SortEvent::Merge { .. } => {}
+            SortEvent::BufferCompare(..) | SortEvent::BufferWrite(..) => self.observer.observe(event),
+        }
+    }
+}
+
+/**
+ * Compare the elements at `i` and `j`, reporting the comparison.
+ */
+pub(crate) fn compare_at<T, F, O>(arr: &[T], i: usize, j: usize, compare: &mut F, observer: &mut O) -> Ordering
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    observer.observe(SortEvent::Compare(i, j));
+    compare(&arr[i], &arr[j])
+}
+
+/**
+ * Swap the elements at `i` and `j`, reporting the swap.
+ */
+pub(crate) fn swap_at<T, O: SortObserver>(arr: &mut [T], i: usize, j: usize, observer: &mut O) {
+    observer.observe(SortEvent::Swap(i, j));
+    arr.swap(i, j);
+}
+
+/**
+ * Draws every event as a line with a strip of one character per position of the array:
+ *
+ * ```text
+ *      0 compare   ?.?..  0 2
+ *      1 swap      x.x..  0 2
+ *      2 partition <<=>>  0..5 pivot 2..3
+ *      3 merge     (()))  0..2 2..5
+ *      4 write     .w...  1
+ *      5 buffer    .....  write 3
+ * ```
+ *
+ * Partitions mark the smaller elements with `<`, the ones equal to the pivot with `=` and the
+ * greater ones with `>`. Merges mark the left run with `(` and the right run with `)`. Events in a
+ * scratch buffer leave the strip empty.
+ *
+ * Write errors stop the output, `finish` returns the first one.
+ */
+pub struct TextRenderer<W: Write> {
+    out: W,
+    len: usize,
+    step: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> TextRenderer<W> {
+    /**
+     * A renderer for sorting an array of `len` elements.
+     */
+    pub fn new(out: W, len: usize) -> Self {
+        TextRenderer { out, len, step: 0, error: None }
+    }
+
+    /**
+     * The writer, or the first error writing to it.
+     */
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.out.flush().map(|_| self.out),
+        }
+    }
+
+    fn render(&mut self, event: SortEvent) -> io::Result<()> {
+        let mut strip = vec![b'.'; self.len];
+        let len = self.len;
+        let mut mark = |range: std::ops::Range<usize>, c: u8| {
+            for cell in &mut strip[range.start.min(len)..range.end.min(len)] {
+                *cell = c;
+            }
+        };
+        let (name, details) = match event {
+            SortEvent::Compare(i, j) => {
+                mark(i..i + 1, b'?');
+                mark(j..j + 1, b'?');
+                ("compare", format!("{} {}", i, j))
+            }
+            SortEvent::Swap(i, j) => {
+                mark(i..i + 1, b'x');
+                mark(j..j + 1, b'x');
+                ("swap", format!("{} {}", i, j))
+            }
+            SortEvent::Write(i) => {
+                mark(i..i + 1, b'w');
+                ("write", format!("{}", i))
+            }
+            SortEvent::Partition { start, end, pivot_start, pivot_end } => {
+                mark(start..pivot_start, b'<');
+                mark(pivot_start..pivot_end, b'=');
+                mark(pivot_end..end, b'>');
+                ("partition", format!("{}..{} pivot {}..{}", start, end, pivot_start, pivot_end))
+            }
+            SortEvent::Merge { start, mid, end } => {
+                mark(start..mid, b'(');
+                mark(mid..end, b')');
+                ("merge", format!("{}..{} {}..{}", start, mid, mid, end))
+            }
+            SortEvent::BufferCompare(i, j) => ("buffer", format!("compare {} {}", i, j)),
+            SortEvent::BufferWrite(i) => ("buffer", format!("write {}", i)),
+        };
+        writeln!(self.out, "{:>6} {:<9} {}  {}", self.step, name, String::from_utf8_lossy(&strip), details)
+    }
+}
+
+impl<W: Write> SortObserver for TextRenderer<W> {
+    fn observe(&mut self, event: SortEvent) {
+        if self.error.is_none() {
+            if let Err(error) = self.render(event) {
+                self.error = Some(error);
+            }
+        }
+        self.step += 1;
+    }
+}
+
+/**
+ * Writes every event as a JSON object on its own line, e.g.
+ *
+ * ```text
+ * {"step":0,"event":"compare","i":0,"j":2}
+ * {"step":1,"event":"swap","i":0,"j":2}
+ * {"step":2,"event":"write","i":1}
+ * {"step":3,"event":"partition","start":0,"end":5,"pivot_start":2,"pivot_end":3}
+ * {"step":4,"event":"merge","start":0,"mid":2,"end":5}
+ * {"step":5,"event":"buffer_write","i":3}
+ * ```
+ *
+ * Write errors stop the output, `finish` returns the first one.
+ */
+pub struct JsonLinesExporter<W: Write> {
+    out: W,
+    step: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonLinesExporter<W> {
+    pub fn new(out: W) -> Self {
+        JsonLinesExporter { out, step: 0, error: None }
+    }
+
+    /**
+     * The writer, or the first error writing to it.
+     */
+    pub fn finish(mut self) -> io::Result<W> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.out.flush().map(|_| self.out),
+        }
+    }
+
+    fn export(&mut self, event: SortEvent) -> io::Result<()> {
+        write!(self.out, "{{\"step\":{},", self.step)?;
+        match event {
+            SortEvent::Compare(i, j) => write!(self.out, "\"event\":\"compare\",\"i\":{},\"j\":{}", i, j)?,
+            SortEvent::Swap(i, j) => write!(self.out, "\"event\":\"swap\",\"i\":{},\"j\":{}", i, j)?,
+            SortEvent::Write(i) => write!(self.out, "\"event\":\"write\",\"i\":{}", i)?,
+            SortEvent::Partition { start, end, pivot_start, pivot_end } => write!(
+                self.out,
+                "\"event\":\"partition\",\"start\":{},\"end\":{},\"pivot_start\":{},\"pivot_end\":{}",
+                start, end, pivot_start, pivot_end
+            )?,
+            SortEvent::Merge { start, mid, end } => {
+                write!(self.out, "\"event\":\"merge\",\"start\":{},\"mid\":{},\"end\":{}", start, mid, end)?
+            }
+            SortEvent::BufferCompare(i, j) => write!(self.out, "\"event\":\"buffer_compare\",\"i\":{},\"j\":{}", i, j)?,
+            SortEvent::BufferWrite(i) => write!(self.out, "\"event\":\"buffer_write\",\"i\":{}", i)?,
+        }
+        writeln!(self.out, "}}")
+    }
+}
+
+impl<W: Write> SortObserver for JsonLinesExporter<W> {
+    fn observe(&mut self, event: SortEvent) {
+        if self.error.is_none() {
+            if let Err(error) = self.export(event) {
+                self.error = Some(error);
+            }
+        }
+        self.step += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::bucket_sort::bucket_sort_by_key_observed;
+    use crate::sorting::counting_sort::counting_sort_by_key_observed;
+    use crate::sorting::insertion_sort::insertion_sort_by_observed;
+    use crate::sorting::radix_sort::{msd_radix_sort_by_key_observed, radix_sort_by_key_observed};
+    use crate::sorting::sorter::sorters;
+
+    #[test]
+    fn test_events_replay() {
+        let input: Vec<u32> = (0..200).map(|i| i * 7919 % 211 / 3).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        for sorter in sorters::<u32>() {
+            let mut arr = input.clone();
+            let mut events = Vec::new();
+            sorter.sort_by_observed(&mut arr, &mut u32::cmp, &mut events);
+            assert_eq!(arr, expected, "{}", sorter.name());
+
+            let mut replayed = input.clone();
+            let mut only_swaps = true;
+            for event in events {
+                match event {
+                    SortEvent::Compare(i, j) => assert!(i < arr.len() && j < arr.len() && i != j, "{}", sorter.name()),
+                    SortEvent::Swap(i, j) => replayed.swap(i, j),
+                    SortEvent::Write(i) => {
+                        assert!(i < arr.len(), "{}", sorter.name());
+                        only_swaps = false;
+                    }
+                    SortEvent::Partition { start, end, pivot_start, pivot_end } => {
+                        assert!(start <= pivot_start && pivot_start < pivot_end && pivot_end <= end && end <= arr.len())
+                    }
+                    SortEvent::Merge { start, mid, end } => assert!(start < mid && mid < end && end <= arr.len()),
+                    SortEvent::BufferCompare(..) | SortEvent::BufferWrite(..) => panic!("{}", sorter.name()),
+                }
+            }
+            // The positions are those of the whole array, so replaying the swaps sorts the input
+            if only_swaps {
+                assert_eq!(replayed, expected, "{}", sorter.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_sorts_by_key_events() {
+        let input: Vec<u32> = (0..200).map(|i| i * 7919 % 211 / 3).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        type KeySort = fn(&mut [u32], &mut Vec<SortEvent>);
+        let sorts: [(&str, KeySort); 5] = [
+            ("counting", |arr, events| counting_sort_by_key_observed(arr, |&x| x as usize, events)),
+            ("counting wide", |arr, events| counting_sort_by_key_observed(arr, |&x| x as usize * 1_000_000, events)),
+            ("radix", |arr, events| radix_sort_by_key_observed(arr, |&x| x, events)),
+            ("msd radix", |arr, events| {
+                let mut keys: Vec<[u8; 4]> = arr.iter().map(|x| x.to_be_bytes()).collect();
+                msd_radix_sort_by_key_observed(&mut keys, |key| &key[..], events);
+                for (x, key) in arr.iter_mut().zip(keys) {
+                    *x = u32::from_be_bytes(key);
+                }
+            }),
+            ("bucket", |arr, events| bucket_sort_by_key_observed(arr, |&x| x as f64, events)),
+        ];
+        for (name, sort) in sorts {
+            let mut arr = input.clone();
+            let mut events = Vec::new();
+            sort(&mut arr, &mut events);
+            assert_eq!(arr, expected, "{}", name);
+
+            // Only the final swaps touch the array, everything else happens in a scratch buffer
+            let mut replayed = input.clone();
+            let mut in_buffer = 0;
+            for event in events {
+                match event {
+                    SortEvent::Swap(i, j) => replayed.swap(i, j),
+                    SortEvent::BufferCompare(i, j) => {
+                        assert!(i < arr.len() && j < arr.len() && i != j, "{}", name);
+                        in_buffer += 1;
+                    }
+                    SortEvent::BufferWrite(i) => {
+                        assert!(i < arr.len(), "{}", name);
+                        in_buffer += 1;
+                    }
+                    event => panic!("{} {:?}", name, event),
+                }
+            }
+            assert!(in_buffer > 0, "{}", name);
+            assert_eq!(replayed, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_renderers() {
+        let mut arr = [3, 1, 2];
+        let mut renderer = TextRenderer::new(Vec::new(), arr.len());
+        insertion_sort_by_observed(&mut arr, |a, b| a.cmp(b), &mut renderer);
+        assert_eq!(
+            String::from_utf8(renderer.finish().unwrap()).unwrap(),
+            "     0 compare   ??.  0 1\n     1 swap      xx.  0 1\n     2 compare   .??  1 2\n     3 swap      .xx  1 2\n     4 compare   ??.  0 1\n"
+        );
+
+        let mut exporter = JsonLinesExporter::new(Vec::new());
+        exporter.observe(SortEvent::Swap(0, 2));
+        exporter.observe(SortEvent::Partition { start: 0, end: 5, pivot_start: 2, pivot_end: 3 });
+        exporter.observe(SortEvent::Merge { start: 0, mid: 2, end: 5 });
+        exporter.observe(SortEvent::BufferWrite(3));
+        assert_eq!(
+            String::from_utf8(exporter.finish().unwrap()).unwrap(),
+            "{\"step\":0,\"event\":\"swap\",\"i\":0,\"j\":2}\n\
+             {\"step\":1,\"event\":\"partition\",\"start\":0,\"end\":5,\"pivot_start\":2,\"pivot_end\":3}\n\
+             {\"step\":2,\"event\":\"merge\",\"start\":0,\"mid\":2,\"end\":5}\n\
+             {\"step\":3,\"event\":\"buffer_write\",\"i\":3}\n"
+        );
+
+        let mut renderer = TextRenderer::new(Vec::new(), 3);
+        renderer.observe(SortEvent::BufferCompare(4, 5));
+        assert_eq!(String::from_utf8(renderer.finish().unwrap()).unwrap(), "     0 buffer    ...  compare 4 5\n");
+    }
+}
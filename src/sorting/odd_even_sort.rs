@@ -0,0 +1,84 @@
+/**
+ * Odd-Even Sort
+ *
+ * Time Complexity: O(n^2), O(n) if the array is already sorted
+ * Space Complexity: O(1)
+ *
+ * Also known as brick sort. Alternates between comparing the pairs at odd positions (1, 2), (3, 4), ...
+ * and the pairs at even positions (0, 1), (2, 3), ..., swapping pairs that are out of order. The pairs of
+ * one phase do not overlap, so on parallel hardware every phase takes a single step. At most n phases
+ * are needed, and the sort stops after an odd and an even phase without swaps.
+ *
+ * Equal elements are never swapped, so the sort is stable.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
+
+pub fn odd_even_sort<T: Ord>(arr: &mut [T]) {
+    odd_even_sort_by(arr, T::cmp);
+}
+
+pub fn odd_even_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn odd_even_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+pub fn odd_even_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    odd_even_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let mut sorted = false;
+    while !sorted {
+        sorted = true;
+        for first in [1, 0] {
+            for i in (first..arr.len().saturating_sub(1)).step_by(2) {
+                if compare_at(arr, i, i + 1, compare, observer) == Ordering::Greater {
+                    swap_at(arr, i, i + 1, observer);
+                    sorted = false;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_odd_even_sort() {
+        let mut arr: Vec<i32> = (0..101).map(|i| (i * 37) % 101 - 50).collect();
+        odd_even_sort(&mut arr);
+        assert_eq!(arr, (-50..51).collect::<Vec<_>>());
+
+        let mut records = [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')];
+        odd_even_sort_by_key(&mut records, |record| record.0);
+        assert_eq!(records, [(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c')]);
+
+        let mut single = [1];
+        odd_even_sort(&mut single);
+    }
+}
@@ -0,0 +1,240 @@
+/**
+ * Parallel Sorting
+ *
+ * Multi-threaded versions of `merge_sort` and `quick_sort`, using only `std::thread::scope`.
+ *
+ * Both split the threads between the two halves of the recursion, and ranges of at most
+ * `SEQUENTIAL_CUTOFF` elements (or once a half is down to a single thread) are sorted with the
+ * sequential version.
+ *
+ * - `parallel_merge_sort` sorts the halves in parallel and then merges them in parallel as well: the
+ *   middle element of the longer run is looked up in the other run with a binary search, which splits
+ *   the merge into two independent merges into disjoint parts of a buffer.
+ *   Time Complexity: O(n log n) work, O(log^3 n) span, Space Complexity: O(n)
+ * - `parallel_quick_sort` is the same introsort as `quick_sort`, with the two sides of every
+ *   partition sorted in parallel. The partitions themselves are sequential, so the first one over the
+ *   whole array limits the speedup.
+ *   Time Complexity: O(n log n) work, O(n) span, Space Complexity: O(log n)
+ *
+ * The results are always exactly those of the sequential versions: the merge sort is stable, and the
+ * quick sort makes the same partitions whichever thread runs them.
+ */
+use std::cmp::Ordering;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::thread;
+
+use crate::sorting::merge_sort::merge_sort_by;
+use crate::sorting::quick_sort::{choose_pivot, depth_limit, introsort, partition};
+
+// Below this many elements the threads cost more than they save.
+const SEQUENTIAL_CUTOFF: usize = 4096;
+
+pub fn parallel_merge_sort<T: Ord + Send>(arr: &mut [T], threads: usize) {
+    parallel_merge_sort_by(arr, threads, T::cmp);
+}
+
+/**
+ * Sort with a comparator on up to `threads` threads. The comparator is shared by the threads, so
+ * it has to be `Fn + Sync`.
+ */
+pub fn parallel_merge_sort_by<T, F>(arr: &mut [T], threads: usize, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let mut buffer: Vec<MaybeUninit<T>> = (0..arr.len()).map(|_| MaybeUninit::uninit()).collect();
+    merge_sort(arr, &mut buffer, &compare, threads.max(1));
+}
+
+pub fn parallel_merge_sort_by_key<T, K, F>(arr: &mut [T], threads: usize, key: F)
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    parallel_merge_sort_by(arr, threads, |a, b| key(a).cmp(&key(b)));
+}
+
+pub fn parallel_quick_sort<T: Ord + Send>(arr: &mut [T], threads: usize) {
+    parallel_quick_sort_by(arr, threads, T::cmp);
+}
+
+/**
+ * Sort with a comparator on up to `threads` threads. The comparator is shared by the threads, so
+ * it has to be `Fn + Sync`.
+ */
+pub fn parallel_quick_sort_by<T, F>(arr: &mut [T], threads: usize, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    quick_sort(arr, depth_limit(arr.len()), &compare, threads.max(1));
+}
+
+pub fn parallel_quick_sort_by_key<T, K, F>(arr: &mut [T], threads: usize, key: F)
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    parallel_quick_sort_by(arr, threads, |a, b| key(a).cmp(&key(b)));
+}
+
+fn merge_sort<T, F>(arr: &mut [T], buffer: &mut [MaybeUninit<T>], compare: &F, threads: usize)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let len = arr.len();
+    if threads == 1 || len <= SEQUENTIAL_CUTOFF {
+        merge_sort_by(arr, compare);
+        return;
+    }
+
+    let mid = len / 2;
+    {
+        let (left, right) = arr.split_at_mut(mid);
+        let (left_buffer, right_buffer) = buffer.split_at_mut(mid);
+        thread::scope(|scope| {
+            scope.spawn(|| merge_sort(left, left_buffer, compare, threads / 2));
+            merge_sort(right, right_buffer, compare, threads - threads / 2);
+        });
+    }
+    if compare(&arr[mid], &arr[mid - 1]) != Ordering::Less {
+        return;
+    }
+
+    let (left, right) = arr.split_at_mut(mid);
+    merge(left, right, buffer, compare, threads);
+    // Safety: the merge copied every element of `arr` into the buffer exactly once. Until now `arr`
+    // still owned them (the buffer never drops its contents), so if `compare` panicked above nothing
+    // was duplicated; from here on the copies in `arr` are overwritten by the merged order.
+    unsafe {
+        ptr::copy_nonoverlapping(buffer.as_ptr() as *const T, arr.as_mut_ptr(), len);
+    }
+}
+
+/**
+ * Merge the sorted runs `left` and `right` into `out` by copying the elements bitwise. The runs are
+ * only read, they are `&mut` so that they can be sent to other threads without `T: Sync`.
+ */
+fn merge<T, F>(left: &mut [T], right: &mut [T], out: &mut [MaybeUninit<T>], compare: &F, threads: usize)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if threads == 1 || out.len() <= SEQUENTIAL_CUTOFF {
+        merge_sequential(left, right, out, compare);
+        return;
+    }
+
+    // Split at the middle of the longer run. Equal elements of `left` have to end up before those of
+    // `right`, so they go to the first half
+    let (left_split, right_split) = if left.len() >= right.len() {
+        let mid = left.len() / 2;
+        (mid, right.partition_point(|x| compare(x, &left[mid]) == Ordering::Less))
+    } else {
+        let mid = right.len() / 2;
+        (left.partition_point(|x| compare(x, &right[mid]) != Ordering::Greater), mid)
+    };
+    let (left_low, left_high) = left.split_at_mut(left_split);
+    let (right_low, right_high) = right.split_at_mut(right_split);
+    let (out_low, out_high) = out.split_at_mut(left_split + right_split);
+    thread::scope(|scope| {
+        scope.spawn(|| merge(left_low, right_low, out_low, compare, threads / 2));
+        merge(left_high, right_high, out_high, compare, threads - threads / 2);
+    });
+}
+
+fn merge_sequential<T, F>(left: &[T], right: &[T], out: &mut [MaybeUninit<T>], compare: &F)
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let (mut i, mut j) = (0, 0);
+    for slot in out.iter_mut() {
+        let from_left = j == right.len() || (i < left.len() && compare(&right[j], &left[i]) != Ordering::Less);
+        let element = if from_left {
+            i += 1;
+            &left[i - 1]
+        } else {
+            j += 1;
+            &right[j - 1]
+        };
+        // Safety: a bitwise copy, `merge_sort` makes sure that only one of the two is ever dropped.
+        slot.write(unsafe { ptr::read(element) });
+    }
+}
+
+fn quick_sort<T, F>(arr: &mut [T], mut depth_limit: usize, compare: &F, threads: usize)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let mut compare_mut = |a: &T, b: &T| compare(a, b);
+    // Too deep means the pivots are bad, the sequential introsort falls back to heap sort
+    if threads == 1 || arr.len() <= SEQUENTIAL_CUTOFF || depth_limit == 0 {
+        introsort(arr, 0, depth_limit, &mut compare_mut, &mut ());
+        return;
+    }
+    depth_limit -= 1;
+
+    // The same steps as an iteration of `introsort`
+    let pivot = choose_pivot(arr, &mut compare_mut, &mut ());
+    let (less, greater) = partition(arr, pivot, &mut compare_mut, &mut ());
+    let (left, rest) = arr.split_at_mut(less);
+    let right = &mut rest[greater - less..];
+    thread::scope(|scope| {
+        scope.spawn(|| quick_sort(left, depth_limit, compare, threads / 2));
+        quick_sort(right, depth_limit, compare, threads - threads / 2);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::Rng;
+    use crate::sorting::quick_sort::quick_sort_by;
+
+    #[test]
+    fn test_parallel_merge_sort() {
+        // Records with many equal keys, the ids show whether the order of equal keys is kept
+        let mut rng = Rng::new(1);
+        let records: Vec<(u64, String)> = (0..50_000).map(|id| (rng.below(1000), id.to_string())).collect();
+        let mut expected = records.clone();
+        merge_sort_by(&mut expected, |a, b| a.0.cmp(&b.0));
+        for threads in [1, 2, 3, 8] {
+            let mut actual = records.clone();
+            parallel_merge_sort_by_key(&mut actual, threads, |record| record.0);
+            assert_eq!(actual, expected, "{} threads", threads);
+        }
+
+        let mut sorted: Vec<u64> = (0..20_000).collect();
+        parallel_merge_sort(&mut sorted, 4);
+        assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_parallel_quick_sort() {
+        let n = 50_000;
+        let mut rng = Rng::new(2);
+        let patterns: Vec<Vec<u64>> = vec![
+            (0..n).map(|_| rng.next_u64()).collect(),
+            (0..n as u64).collect(),
+            (0..n as u64).rev().collect(),
+            (0..n).map(|_| rng.below(7)).collect(),
+        ];
+        for input in patterns {
+            // Comparing by the low bits only, so equal elements can be told apart: the order within
+            // them is the same only if the partitions are
+            let compare = |a: &u64, b: &u64| (a % 4096).cmp(&(b % 4096));
+            let mut expected = input.clone();
+            quick_sort_by(&mut expected, compare);
+            for threads in [1, 2, 3, 8] {
+                let mut actual = input.clone();
+                parallel_quick_sort_by(&mut actual, threads, compare);
+                assert_eq!(actual, expected, "{} threads", threads);
+            }
+        }
+    }
+}
@@ -1,49 +1,200 @@
 /** 
  * Quick Sort Algorithm Example
  * 
- * Quick sort is a divide and conquer algorithm that has a time complexity that in the worst case scenario is O(n^2) and memory complexity O(log n)
+ * Quick sort is a divide and conquer algorithm that works by partitioning an array around a pivot, and then
+ * recursively sorting the two sides independently. Plain quick sort is O(n^2) in the worst case, so this is an
+ * introsort:
  * 
- * It is not considered a stable sort.
+ * - The pivot is the median of the first, middle and last element, or for large ranges the median of three such
+ *   medians (Tukey's ninther), so sorted, reversed and organ pipe inputs still split evenly.
+ * - The partition is three-way (Dutch national flag): elements equal to the pivot end up in the middle and are
+ *   never looked at again, so inputs with many duplicates take linear time per distinct value.
+ * - Only the smaller side is sorted recursively, the larger side is handled by the loop, so the stack depth is
+ *   O(log n).
+ * - Ranges of at most 16 elements are finished with insertion sort.
+ * - If the recursion gets deeper than 2 log n, the pivots are clearly bad and the range is heap sorted instead.
+ * 
+ * Time Complexity: O(n log n)
+ * Space Complexity: O(log n)
  * 
- * It is a recursive algorithm that works by partitioning an array into two halves, and then recursively sorting the two halves independently.
+ * It is not considered a stable sort.
  */
+use std::cmp::Ordering;
+
+use crate::sorting::heap_sort;
+use crate::sorting::insertion_sort;
+use crate::sorting::observer::{compare_at, swap_at, Offset, SortEvent, SortObserver};
+
 pub fn quick_sort<T: Ord>(arr: &mut [T]) {
+    quick_sort_by(arr, T::cmp);
+}
+
+/**
+ * Sort with a comparator, e.g. `|a, b| b.cmp(a)` for descending order.
+ */
+pub fn quick_sort_by<T, F>(arr: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn quick_sort_by_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, &mut compare, observer);
+}
+
+/**
+ * Sort by a key extracted from every element. The key is computed for every comparison.
+ */
+pub fn quick_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quick_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+// Ranges up to this length are insertion sorted
+const INSERTION_THRESHOLD: usize = 16;
+// Ranges from this length on use the ninther as pivot
+const NINTHER_THRESHOLD: usize = 128;
+
+fn sort<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    introsort(arr, 0, depth_limit(arr.len()), compare, observer);
+}
+
+/**
+ * How deep the recursion may get before the range is heap sorted instead: 2 log n.
+ */
+pub(crate) fn depth_limit(len: usize) -> usize {
+    2 * (usize::BITS - len.leading_zeros()) as usize
+}
+
+/**
+ * Sort `arr`, which starts at position `base` of the whole array. The operations are reported with
+ * their positions in the whole array.
+ */
+pub(crate) fn introsort<T, F, O>(mut arr: &mut [T], mut base: usize, mut depth_limit: usize, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    loop {
+        let mut offset = Offset { observer: &mut *observer, base };
+        if arr.len() <= INSERTION_THRESHOLD {
+            insertion_sort::sort(arr, compare, &mut offset);
+            return;
+        }
+        if depth_limit == 0 {
+            heap_sort::sort(arr, compare, &mut offset);
+            return;
+        }
+        depth_limit -= 1;
+
+        let pivot = choose_pivot(arr, compare, &mut offset);
+        let (less, greater) = partition(arr, pivot, compare, &mut offset);
+        offset.observe(SortEvent::Partition { start: 0, end: arr.len(), pivot_start: less, pivot_end: greater });
+        let (left, rest) = std::mem::take(&mut arr).split_at_mut(less);
+        let right = &mut rest[greater - less..];
+        if left.len() < right.len() {
+            introsort(left, base, depth_limit, compare, observer);
+            arr = right;
+            base += greater;
+        } else {
+            introsort(right, base + greater, depth_limit, compare, observer);
+            arr = left;
+        }
+    }
+}
+
+/**
+ * The index of the median of three elements, or of the ninther for large ranges.
+ */
+pub(crate) fn choose_pivot<T, F, O>(arr: &[T], compare: &mut F, observer: &mut O) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
     let len = arr.len();
-    if len < 2 {
-        return;
+    let (first, mid, last) = (0, len / 2, len - 1);
+    if len < NINTHER_THRESHOLD {
+        return median_of_three(arr, first, mid, last, compare, observer);
+    }
+    let step = len / 8;
+    let low = median_of_three(arr, first, first + step, first + 2 * step, compare, observer);
+    let middle = median_of_three(arr, mid - step, mid, mid + step, compare, observer);
+    let high = median_of_three(arr, last - 2 * step, last - step, last, compare, observer);
+    median_of_three(arr, low, middle, high, compare, observer)
+}
+
+fn median_of_three<T, F, O>(arr: &[T], a: usize, b: usize, c: usize, compare: &mut F, observer: &mut O) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let mut less = |x: usize, y: usize| compare_at(arr, x, y, compare, observer) == Ordering::Less;
+    if less(a, b) {
+        if less(b, c) {
+            b
+        } else if less(a, c) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c) {
+        a
+    } else if less(b, c) {
+        c
+    } else {
+        b
     }
-    let pivot = partition(arr);
-    // println!("Pivot: {}", pivot);
-    // println!("Left Before: {:?}", &arr[0..pivot]);
-    quick_sort(&mut arr[0..pivot]);
-    // println!("Left After: {:?}", &arr[0..pivot]);
-    // println!("Right Before: {:?}", &arr[pivot + 1..len]);
-    quick_sort(&mut arr[pivot + 1..len]);
-    // println!("Right: {:?}", &arr[pivot + 1..len]);
 }
 
 /**
- * Partition the array into two halves.
+ * Partition the array into three parts around the element at `pivot`.
  * 
- * The pivot is the last element in the array.
+ * The left part contains all elements less than the pivot.
  * 
- * The left half contains all elements less than or equal to the pivot.
+ * The middle part contains all elements equal to the pivot.
  * 
- * The right half contains all elements greater than the pivot.
+ * The right part contains all elements greater than the pivot.
+ * 
+ * @return The start and end of the middle part
  */
-fn partition<T: Ord>(arr: &mut [T]) -> usize {
-    let len = arr.len();
-    let pivot = len / 2;
-    arr.swap(pivot, len - 1);
-    let mut store = 0;
-    for i in 0..len - 1 {
-        if arr[i] <= arr[len - 1] {
-            arr.swap(i, store);
-            store += 1;
+pub(crate) fn partition<T, F, O>(arr: &mut [T], pivot: usize, compare: &mut F, observer: &mut O) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    // arr[less] is always a copy of the pivot, arr[less..i] are all equal to it
+    swap_at(arr, 0, pivot, observer);
+    let (mut less, mut i, mut greater) = (0, 1, arr.len());
+    while i < greater {
+        match compare_at(arr, i, less, compare, observer) {
+            Ordering::Less => {
+                swap_at(arr, less, i, observer);
+                less += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                greater -= 1;
+                swap_at(arr, i, greater, observer);
+            }
+            Ordering::Equal => i += 1,
         }
     }
-    arr.swap(store, len - 1);
-    store
+    (less, greater)
 }
 
 #[cfg(test)]
@@ -65,4 +216,41 @@ mod tests {
         println!("After: {:?}", arr);
         assert_eq!(arr, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
     }
+
+    #[test]
+    fn test_quick_sort_by() {
+        // Records sorted by several fields: age descending, then name
+        let mut people = vec![("bob", 30), ("alice", 25), ("carol", 30), ("dave", 25)];
+        quick_sort_by(&mut people, |a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        assert_eq!(people, vec![("bob", 30), ("carol", 30), ("alice", 25), ("dave", 25)]);
+        quick_sort_by_key(&mut people, |person| person.0);
+        assert_eq!(people, vec![("alice", 25), ("bob", 30), ("carol", 30), ("dave", 25)]);
+    }
+
+    #[test]
+    fn test_quick_sort_patterns() {
+        let n = 20_000;
+        let patterns: Vec<(&str, Vec<u32>)> = vec![
+            ("sorted", (0..n).collect()),
+            ("reversed", (0..n).rev().collect()),
+            ("equal", vec![7; n as usize]),
+            ("few distinct", (0..n).map(|i| i * 7919 % 3).collect()),
+            ("organ pipe", (0..n).map(|i| i.min(n - i)).collect()),
+            ("sawtooth", (0..n).map(|i| i % 64).collect()),
+            ("random", (0..n).map(|i| i.wrapping_mul(2654435761) >> 8).collect()),
+        ];
+        let log_n = (u32::BITS - n.leading_zeros()) as usize;
+        for (name, arr) in patterns {
+            let mut expected = arr.clone();
+            expected.sort();
+            let mut actual = arr.clone();
+            let mut comparisons = 0;
+            quick_sort_by(&mut actual, |a, b| {
+                comparisons += 1;
+                a.cmp(b)
+            });
+            assert_eq!(actual, expected, "{}", name);
+            assert!(comparisons < 3 * n as usize * log_n, "{}: {} comparisons", name, comparisons);
+        }
+    }
 }
\ No newline at end of file
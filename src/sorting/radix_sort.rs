@@ -0,0 +1,325 @@
+/**
+ * Radix Sort
+ *
+ * Radix sort does not compare elements either. It sorts by one digit of the key at a time, here one byte,
+ * with a counting sort per digit.
+ *
+ * - LSD (least significant digit first) radix sort does one stable pass per byte of a fixed size key,
+ *   starting with the lowest byte. After the pass for the highest byte the elements are sorted.
+ *   Time Complexity: O(n * w) for w byte keys, Space Complexity: O(n)
+ * - MSD (most significant digit first) radix sort splits the elements into 256 buckets by their first
+ *   byte and sorts every bucket by the following bytes, so it works for keys of different lengths like
+ *   strings and stops as soon as a bucket holds a single element.
+ *   Time Complexity: O(n + total length of the distinguishing prefixes), Space Complexity: O(n)
+ *
+ * Every sort here is stable.
+ */
+use crate::sorting::counting_sort::apply_order;
+use crate::sorting::insertion_sort;
+use crate::sorting::observer::{InBuffer, SortEvent, SortObserver};
+
+/**
+ * A fixed size key that LSD radix sort can sort by.
+ *
+ * `radix_bits` maps the key to an unsigned integer with the same order, of which only the lowest
+ * `BYTES` bytes are used:
+ *
+ * - Unsigned integers are used as they are.
+ * - Signed integers get their sign bit flipped, so negative numbers come before positive ones.
+ * - Floats use their IEEE 754 bits. Negative floats have all bits flipped (a larger magnitude is a
+ *   smaller number), positive floats only the sign bit. The order is that of `f64::total_cmp`:
+ *   `-NaN < -inf < ... < -0.0 < 0.0 < ... < inf < NaN`.
+ */
+pub trait RadixKey: Copy {
+    const BYTES: usize;
+
+    fn radix_bits(self) -> u64;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn radix_bits(self) -> u64 {
+                self as u64
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $unsigned:ty),*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn radix_bits(self) -> u64 {
+                ((self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1))) as u64
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_radix_key_float {
+    ($($t:ty),*) => {$(
+        impl RadixKey for $t {
+            const BYTES: usize = std::mem::size_of::<$t>();
+
+            fn radix_bits(self) -> u64 {
+                let bits = self.to_bits();
+                let sign = 1 << (Self::BYTES * 8 - 1);
+                (if bits & sign != 0 { !bits } else { bits | sign }) as u64
+            }
+        }
+    )*};
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize);
+impl_radix_key_float!(f32, f64);
+
+impl RadixKey for char {
+    const BYTES: usize = 3;
+
+    fn radix_bits(self) -> u64 {
+        self as u64
+    }
+}
+
+/**
+ * LSD radix sort of keys such as `u32` ids, `i64` timestamps or `f64` scores.
+ *
+ * Passes over a byte that is the same for every key are skipped, so small keys in a wide type (e.g.
+ * ids below 2^24 in a `u64`) only cost the passes they need.
+ */
+pub fn radix_sort<K: RadixKey>(arr: &mut [K]) {
+    lsd_sort(arr, K::BYTES, |key| key.radix_bits(), &mut ());
+}
+
+/**
+ * LSD radix sort by a key extracted from every element. The key is computed once per element, the
+ * passes move `(key, index)` pairs and the elements themselves are moved only once at the end.
+ */
+pub fn radix_sort_by_key<T, K, F>(arr: &mut [T], key: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    sort_by_key(arr, key, &mut ());
+}
+
+/**
+ * Sort by a key and report every step to `observer`. Every pass writes each `(key, index)` pair once
+ * to a scratch buffer, and the elements are moved into place with swaps at the end.
+ */
+pub fn radix_sort_by_key_observed<T, K, F, O>(arr: &mut [T], key: F, observer: &mut O)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+    O: SortObserver,
+{
+    sort_by_key(arr, key, observer);
+}
+
+fn sort_by_key<T, K, F, O>(arr: &mut [T], mut key: F, observer: &mut O)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+    O: SortObserver,
+{
+    let mut pairs: Vec<(u64, usize)> = arr.iter().enumerate().map(|(i, element)| (key(element).radix_bits(), i)).collect();
+    lsd_sort(&mut pairs, K::BYTES, |pair| pair.0, observer);
+    apply_order(arr, pairs.into_iter().map(|(_, i)| i).collect(), observer);
+}
+
+/**
+ * The LSD radix sort passes. The callers sort scratch buffers of `(key, index)` pairs with it when
+ * observed, so the writes are reported as `BufferWrite`s.
+ */
+pub(crate) fn lsd_sort<X: Copy, B: Fn(&X) -> u64, O: SortObserver>(arr: &mut [X], bytes: usize, bits: B, observer: &mut O) {
+    let len = arr.len();
+    if len < 2 {
+        return;
+    }
+
+    // The histograms of all bytes in a single pass over the keys
+    let mut counts = vec![[0usize; 256]; bytes];
+    for element in arr.iter() {
+        let bits = bits(element);
+        for (byte, count) in counts.iter_mut().enumerate() {
+            count[(bits >> (8 * byte)) as usize & 0xff] += 1;
+        }
+    }
+
+    let mut buffer = arr.to_vec();
+    let mut in_buffer = false;
+    for (byte, count) in counts.iter().enumerate() {
+        if count.contains(&len) {
+            continue;
+        }
+        let mut offsets = [0; 256];
+        let mut next = 0;
+        for (offset, &count) in offsets.iter_mut().zip(count.iter()) {
+            *offset = next;
+            next += count;
+        }
+
+        let (source, target): (&[X], &mut [X]) = if in_buffer { (&buffer, arr) } else { (arr, &mut buffer) };
+        for element in source {
+            let digit = (bits(element) >> (8 * byte)) as usize & 0xff;
+            target[offsets[digit]] = *element;
+            observer.observe(SortEvent::BufferWrite(offsets[digit]));
+            offsets[digit] += 1;
+        }
+        in_buffer = !in_buffer;
+    }
+    if in_buffer {
+        arr.copy_from_slice(&buffer);
+        for i in 0..len {
+            observer.observe(SortEvent::BufferWrite(i));
+        }
+    }
+}
+
+// Buckets up to this size are finished with an insertion sort
+const MSD_CUTOFF: usize = 32;
+
+/**
+ * MSD radix sort of byte strings, e.g. `String`, `&str` or `Vec<u8>`. Strings are ordered byte by
+ * byte, which for UTF-8 is the same as ordering by code point.
+ */
+pub fn msd_radix_sort<S: AsRef<[u8]>>(arr: &mut [S]) {
+    msd_radix_sort_by_key(arr, |s| s.as_ref());
+}
+
+/**
+ * MSD radix sort by a byte string borrowed from every element.
+ */
+pub fn msd_radix_sort_by_key<T, F>(arr: &mut [T], key: F)
+where
+    F: Fn(&T) -> &[u8],
+{
+    msd_sort(arr, key, &mut ());
+}
+
+/**
+ * Sort by a key and report every step to `observer`. The order of the elements is sorted in a scratch
+ * buffer first, the comparisons are those of the small buckets finished with an insertion sort.
+ */
+pub fn msd_radix_sort_by_key_observed<T, F, O>(arr: &mut [T], key: F, observer: &mut O)
+where
+    F: Fn(&T) -> &[u8],
+    O: SortObserver,
+{
+    msd_sort(arr, key, observer);
+}
+
+fn msd_sort<T, F, O>(arr: &mut [T], key: F, observer: &mut O)
+where
+    F: Fn(&T) -> &[u8],
+    O: SortObserver,
+{
+    let len = arr.len();
+    let mut order: Vec<usize> = (0..len).collect();
+    let mut buffer = vec![0; len];
+    // Ranges of `order` whose keys agree on their first `depth` bytes
+    let mut stack = vec![(0, len, 0)];
+
+    while let Some((start, end, depth)) = stack.pop() {
+        let range = &mut order[start..end];
+        if range.len() <= MSD_CUTOFF {
+            let mut compare = |&a: &usize, &b: &usize| key(&arr[a])[depth..].cmp(&key(&arr[b])[depth..]);
+            insertion_sort::sort(range, &mut compare, &mut InBuffer { observer: &mut *observer, base: start });
+            continue;
+        }
+
+        // Bucket 0 holds the keys that end here, bucket b + 1 the keys with byte b at `depth`
+        let bucket = |i: usize| key(&arr[i]).get(depth).map_or(0, |&b| b as usize + 1);
+        let mut counts = [0usize; 257];
+        for &i in range.iter() {
+            counts[bucket(i)] += 1;
+        }
+        let mut offsets = [0; 257];
+        let mut next = 0;
+        for (offset, &count) in offsets.iter_mut().zip(counts.iter()) {
+            *offset = next;
+            next += count;
+        }
+        for &i in range.iter() {
+            let b = bucket(i);
+            buffer[offsets[b]] = i;
+            offsets[b] += 1;
+        }
+        range.copy_from_slice(&buffer[..range.len()]);
+        for position in start..end {
+            observer.observe(SortEvent::BufferWrite(position));
+        }
+
+        let mut bucket_start = start + counts[0];
+        for &count in &counts[1..] {
+            if count > 1 {
+                stack.push((bucket_start, bucket_start + count, depth + 1));
+            }
+            bucket_start += count;
+        }
+    }
+
+    apply_order(arr, order, observer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::generators::Rng;
+
+    #[test]
+    fn test_radix_sort() {
+        let mut rng = Rng::new(1);
+        let mut ids: Vec<u32> = (0..10_000).map(|_| rng.next_u64() as u32).collect();
+        let mut expected = ids.clone();
+        expected.sort();
+        radix_sort(&mut ids);
+        assert_eq!(ids, expected);
+
+        let mut signed: Vec<i64> = (0..10_000).map(|_| rng.next_u64()).map(|x| x as i64 >> (x % 40)).collect();
+        signed.extend([i64::MIN, i64::MAX, 0, -1]);
+        let mut expected = signed.clone();
+        expected.sort();
+        radix_sort(&mut signed);
+        assert_eq!(signed, expected);
+
+        let mut floats = vec![2.5f64, -0.0, 0.0, f64::INFINITY, -1e300, 1e-300, f64::NEG_INFINITY, -2.5, 3.0];
+        radix_sort(&mut floats);
+        let mut expected = floats.clone();
+        expected.sort_by(f64::total_cmp);
+        assert_eq!(floats.iter().map(|x| x.to_bits()).collect::<Vec<_>>(), expected.iter().map(|x| x.to_bits()).collect::<Vec<_>>());
+        assert!(floats[0] == f64::NEG_INFINITY && floats[3].is_sign_negative() && floats[4].is_sign_positive());
+
+        // Records by a float score, stable for equal scores
+        let mut records: Vec<(String, f32)> =
+            (0..1000).map(|i| (i.to_string(), rng.below(21) as f32 / 4.0 - 2.5)).collect();
+        let mut expected = records.clone();
+        expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+        radix_sort_by_key(&mut records, |record| record.1);
+        assert_eq!(records, expected);
+    }
+
+    #[test]
+    fn test_msd_radix_sort() {
+        // Few distinct bytes and short lengths, so there are many shared prefixes and keys that are
+        // prefixes of other keys
+        let mut rng = Rng::new(4);
+        let mut words: Vec<Vec<u8>> = (0..5000)
+            .map(|_| rng.next_u64())
+            .map(|x| (0..x % 7).map(|i| b"ab\xff"[(x >> (3 + 2 * i)) as usize % 3]).collect())
+            .collect();
+        let mut expected = words.clone();
+        expected.sort();
+        msd_radix_sort(&mut words);
+        assert_eq!(words, expected);
+
+        let mut paths = vec![(3, "usr/bin"), (1, "etc"), (4, "usr"), (2, "etc"), (5, "bin")];
+        msd_radix_sort_by_key(&mut paths, |path| path.1.as_bytes());
+        assert_eq!(paths, vec![(5, "bin"), (1, "etc"), (2, "etc"), (4, "usr"), (3, "usr/bin")]);
+    }
+}
@@ -2,26 +2,71 @@
 // Path: src/sorting/selection_sort.rs
 use std::cmp::Ordering;
 
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
+
 /** Selection Sort Algorithm Example 
  * 
  * Time Complexity: O(n^2)
  * Memory Complexity: O(1)
  *  
  * Selection sort is a simple sorting algorithm with a time complexity of O(n^2).
+ * 
+ * There is no early exit: finding the minimum of the rest always takes a full scan, even if the
+ * array is already sorted. In exchange it does at most n - 1 swaps.
  */
 pub fn selection_sort<T: Ord>(array: &mut [T]) {
+    selection_sort_by(array, T::cmp);
+}
+
+/**
+ * Sort with a comparator, e.g. `|a, b| b.cmp(a)` for descending order.
+ */
+pub fn selection_sort_by<T, F>(array: &mut [T], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(array, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn selection_sort_by_observed<T, F, O>(array: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(array, &mut compare, observer);
+}
+
+/**
+ * Sort by a key extracted from every element. The key is computed for every comparison.
+ */
+pub fn selection_sort_by_key<T, K, F>(array: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    selection_sort_by(array, |a, b| key(a).cmp(&key(b)));
+}
+
+fn sort<T, F, O>(array: &mut [T], compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
     let len = array.len();
     for i in 0..len {
         let mut min = i;
         for j in i + 1..len {
-            if array[j] < array[min] {
+            if compare_at(array, j, min, compare, observer) == Ordering::Less {
                 min = j;
             }
         }
         if min != i {
-            array.swap(i, min);
+            swap_at(array, i, min, observer);
         }
-    }   
+    }
 }
 
 #[cfg(test)]
@@ -34,4 +79,14 @@ mod tests {
         selection_sort(&mut arr);
         assert_eq!(arr, [1, 2, 3, 4, 5]);
     }
+
+    #[test]
+    fn test_selection_sort_by() {
+        let mut scores = [2.5, -1.0, 9.75, 0.0];
+        selection_sort_by(&mut scores, |a: &f64, b| b.total_cmp(a));
+        assert_eq!(scores, [9.75, 2.5, 0.0, -1.0]);
+        let mut words = ["ccc", "a", "bb"];
+        selection_sort_by_key(&mut words, |word| word.len());
+        assert_eq!(words, ["a", "bb", "ccc"]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,181 @@
+/**
+ * Shell Sort
+ *
+ * Time Complexity: depends on the gap sequence, about O(n^(4/3)) for Sedgewick's and Ciura's
+ * Space Complexity: O(1)
+ *
+ * Insertion sort moves elements one position at a time, so an element far from its place costs many
+ * moves. Shell sort first insertion sorts the elements that are `gap` apart, for a decreasing sequence
+ * of gaps that ends with 1. The large gaps move elements close to their place quickly, and the final
+ * pass with gap 1 is a plain insertion sort of an almost sorted array.
+ *
+ * Gaps larger than the array are skipped, and every pass stops comparing an element as soon as it is
+ * in order with the element `gap` before it, so a sorted array takes one comparison per element per
+ * gap.
+ *
+ * It is not stable.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::observer::{compare_at, swap_at, SortObserver};
+
+/**
+ * The gap sequence of a shell sort.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GapSequence {
+    /// Shell's original n/2, n/4, ..., 1. O(n^2) in the worst case.
+    Shell,
+    /// Sedgewick's 1, 8, 23, 77, 281, ... (4^k + 3 * 2^(k-1) + 1). O(n^(4/3)) in the worst case.
+    Sedgewick,
+    /// Ciura's experimentally found 1, 4, 10, 23, 57, 132, 301, 701, 1750, extended by a factor of 2.25.
+    /// The fastest known sequence in practice.
+    Ciura,
+}
+
+impl GapSequence {
+    /**
+     * The gaps below `len` in decreasing order, always ending with 1.
+     */
+    pub fn gaps(self, len: usize) -> Vec<usize> {
+        let mut gaps = match self {
+            GapSequence::Shell => {
+                let mut gaps = Vec::new();
+                let mut gap = len / 2;
+                while gap > 0 {
+                    gaps.push(gap);
+                    gap /= 2;
+                }
+                gaps.reverse();
+                gaps
+            }
+            GapSequence::Sedgewick => {
+                let mut gaps = vec![1];
+                for k in 1.. {
+                    let gap = 4usize.saturating_pow(k) + 3 * (1 << (k - 1)) + 1;
+                    if gap >= len {
+                        break;
+                    }
+                    gaps.push(gap);
+                }
+                gaps
+            }
+            GapSequence::Ciura => {
+                let mut gaps = vec![1, 4, 10, 23, 57, 132, 301, 701, 1750];
+                while let Some(&last) = gaps.last() {
+                    if last >= len {
+                        break;
+                    }
+                    gaps.push(last * 9 / 4);
+                }
+                gaps
+            }
+        };
+        gaps.retain(|&gap| gap == 1 || gap < len);
+        gaps.reverse();
+        gaps
+    }
+}
+
+/**
+ * Shell sort with Ciura's gaps.
+ */
+pub fn shell_sort<T: Ord>(arr: &mut [T]) {
+    shell_sort_by(arr, T::cmp);
+}
+
+pub fn shell_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    shell_sort_with_gaps_by(arr, GapSequence::Ciura, compare);
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn shell_sort_by_observed<T, F, O>(arr: &mut [T], compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    shell_sort_with_gaps_by_observed(arr, GapSequence::Ciura, compare, observer);
+}
+
+pub fn shell_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    shell_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+pub fn shell_sort_with_gaps<T: Ord>(arr: &mut [T], sequence: GapSequence) {
+    shell_sort_with_gaps_by(arr, sequence, T::cmp);
+}
+
+pub fn shell_sort_with_gaps_by<T, F>(arr: &mut [T], sequence: GapSequence, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort(arr, sequence, &mut compare, &mut ());
+}
+
+/**
+ * Sort with a comparator and report every step to `observer`.
+ */
+pub fn shell_sort_with_gaps_by_observed<T, F, O>(arr: &mut [T], sequence: GapSequence, mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    sort(arr, sequence, &mut compare, observer);
+}
+
+fn sort<T, F, O>(arr: &mut [T], sequence: GapSequence, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    for gap in sequence.gaps(arr.len()) {
+        for i in gap..arr.len() {
+            let mut j = i;
+            while j >= gap && compare_at(arr, j - gap, j, compare, observer) == Ordering::Greater {
+                swap_at(arr, j - gap, j, observer);
+                j -= gap;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gap_sequences() {
+        assert_eq!(GapSequence::Shell.gaps(20), vec![10, 5, 2, 1]);
+        assert_eq!(GapSequence::Sedgewick.gaps(300), vec![281, 77, 23, 8, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(100), vec![57, 23, 10, 4, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(5000), vec![3937, 1750, 701, 301, 132, 57, 23, 10, 4, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(0), vec![1]);
+    }
+
+    #[test]
+    fn test_shell_sort() {
+        let arr: Vec<i64> = (0..3000).map(|i| (i * 7919 % 3001) - 1500).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+        for sequence in [GapSequence::Shell, GapSequence::Sedgewick, GapSequence::Ciura] {
+            let mut actual = arr.clone();
+            shell_sort_with_gaps(&mut actual, sequence);
+            assert_eq!(actual, expected, "{:?}", sequence);
+        }
+
+        let mut words = ["delta", "alpha", "charlie", "bravo", "echo"];
+        shell_sort_by(&mut words, |a, b| b.cmp(a));
+        assert_eq!(words, ["echo", "delta", "charlie", "bravo", "alpha"]);
+        shell_sort_by_key(&mut words, |word| word.as_bytes()[1]);
+        assert_eq!(words[0], "echo");
+    }
+}
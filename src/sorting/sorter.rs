@@ -0,0 +1,185 @@
+/**
+ * Sorter
+ *
+ * A common interface over the sorting algorithms in this module, so the algorithm can be chosen at
+ * runtime, e.g. from a command line flag:
+ *
+ * ```
+ * use data_structures_algorithms_rust::sorting::sorter::{by_name, Sorter};
+ *
+ * let sorter = by_name::<f64>("heap").unwrap();
+ * let mut scores = [2.5, -1.0, 9.75, 0.0];
+ * sorter.sort_by(&mut scores, &mut |a, b| b.total_cmp(a));
+ * assert_eq!(scores, [9.75, 2.5, 0.0, -1.0]);
+ * ```
+ *
+ * The comparator is passed as `&mut dyn FnMut` so that `Box<dyn Sorter<T>>` works.
+ */
+use std::cmp::Ordering;
+
+use crate::sorting::bubble_sort::{bubble_sort_by, bubble_sort_by_observed};
+use crate::sorting::cocktail_shaker_sort::{cocktail_shaker_sort_by, cocktail_shaker_sort_by_observed};
+use crate::sorting::comb_sort::{comb_sort_by, comb_sort_by_observed};
+use crate::sorting::cycle_sort::{cycle_sort_by, cycle_sort_by_observed};
+use crate::sorting::gnome_sort::{gnome_sort_by, gnome_sort_by_observed};
+use crate::sorting::heap_sort::{heap_sort_by, heap_sort_by_observed};
+use crate::sorting::insertion_sort::{binary_insertion_sort_by, binary_insertion_sort_by_observed, insertion_sort_by, insertion_sort_by_observed};
+use crate::sorting::merge_sort::{merge_sort_by, merge_sort_by_observed, natural_merge_sort_by, natural_merge_sort_by_observed};
+use crate::sorting::observer::SortObserver;
+use crate::sorting::odd_even_sort::{odd_even_sort_by, odd_even_sort_by_observed};
+use crate::sorting::quick_sort::{quick_sort_by, quick_sort_by_observed};
+use crate::sorting::selection_sort::{selection_sort_by, selection_sort_by_observed};
+use crate::sorting::shell_sort::{shell_sort_by, shell_sort_by_observed};
+
+pub trait Sorter<T> {
+    /**
+     * A short lowercase name, as accepted by `by_name`.
+     */
+    fn name(&self) -> &'static str;
+
+    /**
+     * Whether elements that compare equal keep their relative order.
+     */
+    fn is_stable(&self) -> bool;
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering);
+
+    /**
+     * Sort with a comparator and report every step to `observer`, e.g. a `SortStats` to count them.
+     */
+    fn sort_by_observed(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering, observer: &mut dyn SortObserver);
+
+    fn sort(&self, arr: &mut [T])
+    where
+        T: Ord,
+    {
+        self.sort_by(arr, &mut T::cmp);
+    }
+
+    fn sort_by_key<K, F>(&self, arr: &mut [T], mut key: F)
+    where
+        Self: Sized,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(arr, &mut |a, b| key(a).cmp(&key(b)));
+    }
+}
+
+impl<T, S: Sorter<T> + ?Sized> Sorter<T> for Box<S> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn is_stable(&self) -> bool {
+        (**self).is_stable()
+    }
+
+    fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+        (**self).sort_by(arr, compare);
+    }
+
+    fn sort_by_observed(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering, observer: &mut dyn SortObserver) {
+        (**self).sort_by_observed(arr, compare, observer);
+    }
+}
+
+// A unit struct per algorithm, implementing `Sorter` by calling its `_by` and `_by_observed` functions.
+macro_rules! sorters {
+    ($($sorter:ident($name:literal, stable: $stable:literal) => $sort_by:path, $sort_by_observed:path;)*) => {
+        $(
+            pub struct $sorter;
+
+            impl<T> Sorter<T> for $sorter {
+                fn name(&self) -> &'static str {
+                    $name
+                }
+
+                fn is_stable(&self) -> bool {
+                    $stable
+                }
+
+                fn sort_by(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+                    $sort_by(arr, compare);
+                }
+
+                fn sort_by_observed(&self, arr: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering, mut observer: &mut dyn SortObserver) {
+                    $sort_by_observed(arr, compare, &mut observer);
+                }
+            }
+        )*
+
+        /**
+         * Every available sorter.
+         */
+        pub fn sorters<T>() -> Vec<Box<dyn Sorter<T>>> {
+            vec![$(Box::new($sorter)),*]
+        }
+    };
+}
+
+sorters! {
+    BubbleSort("bubble", stable: true) => bubble_sort_by, bubble_sort_by_observed;
+    SelectionSort("selection", stable: false) => selection_sort_by, selection_sort_by_observed;
+    InsertionSort("insertion", stable: true) => insertion_sort_by, insertion_sort_by_observed;
+    BinaryInsertionSort("binary_insertion", stable: true) => binary_insertion_sort_by, binary_insertion_sort_by_observed;
+    ShellSort("shell", stable: false) => shell_sort_by, shell_sort_by_observed;
+    CombSort("comb", stable: false) => comb_sort_by, comb_sort_by_observed;
+    CocktailShakerSort("cocktail_shaker", stable: true) => cocktail_shaker_sort_by, cocktail_shaker_sort_by_observed;
+    GnomeSort("gnome", stable: true) => gnome_sort_by, gnome_sort_by_observed;
+    OddEvenSort("odd_even", stable: true) => odd_even_sort_by, odd_even_sort_by_observed;
+    CycleSort("cycle", stable: false) => cycle_sort_by, cycle_sort_by_observed;
+    QuickSort("quick", stable: false) => quick_sort_by, quick_sort_by_observed;
+    MergeSort("merge", stable: true) => merge_sort_by, merge_sort_by_observed;
+    NaturalMergeSort("natural_merge", stable: true) => natural_merge_sort_by, natural_merge_sort_by_observed;
+    HeapSort("heap", stable: false) => heap_sort_by, heap_sort_by_observed;
+}
+
+/**
+ * Look up a sorter by its `name`.
+ */
+pub fn by_name<T>(name: &str) -> Option<Box<dyn Sorter<T>>> {
+    sorters().into_iter().find(|sorter| sorter.name() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Record {
+        team: u8,
+        score: f32,
+        id: u32,
+    }
+
+    #[test]
+    fn test_sorters_agree() {
+        let records: Vec<Record> = (0..40u32)
+            .map(|id| Record { team: (id * 7 % 3) as u8, score: ((id * 13 % 10) as f32) / 4.0, id })
+            .collect();
+        // Team ascending, then score descending
+        let mut compare = |a: &Record, b: &Record| a.team.cmp(&b.team).then(b.score.total_cmp(&a.score));
+        let mut expected = records.clone();
+        expected.sort_by(&mut compare);
+
+        for sorter in sorters::<Record>() {
+            let mut actual = records.clone();
+            sorter.sort_by(&mut actual, &mut compare);
+            if sorter.is_stable() {
+                assert_eq!(actual, expected, "{}", sorter.name());
+            } else {
+                assert!(actual.windows(2).all(|pair| compare(&pair[0], &pair[1]) != Ordering::Greater));
+            }
+
+            let mut ids: Vec<u32> = records.iter().rev().map(|record| record.id).collect();
+            by_name(sorter.name()).unwrap().sort(&mut ids);
+            assert_eq!(ids, (0..40).collect::<Vec<_>>(), "{}", sorter.name());
+        }
+
+        let mut words = ["ccc", "a", "bb"];
+        QuickSort.sort_by_key(&mut words, |word| word.len());
+        assert_eq!(words, ["a", "bb", "ccc"]);
+        assert!(by_name::<u8>("bogo").is_none());
+    }
+}
@@ -0,0 +1,135 @@
+/**
+ * Sort Statistics
+ *
+ * Counts the work a sort does, to compare algorithms on the same input and to check the complexities
+ * in `ALGORITHMS.md` empirically:
+ *
+ * - `comparisons`: calls of the comparator (or, for the sorts by key, comparisons of two keys)
+ * - `swaps`: exchanges of two elements
+ * - `writes`: elements stored into a position of the array, a swap counts as two. Merges also count
+ *   the elements written back from the scratch buffer, and the sorts by key the indices or
+ *   `(key, index)` pairs they write to their scratch buffers.
+ *
+ * `SortStats` is a `SortObserver` that counts the events, so it is filled in by passing it to the
+ * `_observed` variant of a sort, e.g. `quick_sort_by_observed(&mut arr, |a, b| a.cmp(b), &mut stats)`
+ * or, for the sorts by key, `radix_sort_by_key_observed(&mut arr, |x| x.id, &mut stats)`, or to
+ * `Sorter::sort_by_observed`. `stats_table` runs several sorters on several inputs and formats the
+ * counts next to n log n and n^2.
+ */
+use std::fmt::Write;
+
+use crate::sorting::observer::{SortEvent, SortObserver};
+use crate::sorting::sorter::Sorter;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SortStats {
+    pub comparisons: u64,
+    pub swaps: u64,
+    pub writes: u64,
+}
+
+impl SortObserver for SortStats {
+    fn observe(&mut self, event: SortEvent) {
+        match event {
+            SortEvent::Compare(..) | SortEvent::BufferCompare(..) => self.comparisons += 1,
+            SortEvent::Swap(..) => {
+                self.swaps += 1;
+                self.writes += 2;
+            }
+            SortEvent::Write(..) | SortEvent::BufferWrite(..) => self.writes += 1,
+            SortEvent::Partition { .. } | SortEvent::Merge { .. } => {}
+        }
+    }
+}
+
+/**
+ * Run every sorter on every input and format the counts as a table. The `/ n log n` and `/ n^2`
+ * columns divide the comparisons by those bounds: the one that stays about constant as n grows is
+ * the growth rate of the algorithm on that input.
+ */
+pub fn stats_table<T: Clone + Ord>(sorters: &[Box<dyn Sorter<T>>], inputs: &[(&str, Vec<T>)]) -> String {
+    let mut table = String::new();
+    writeln!(
+        table,
+        "{:<18} {:<10} {:>7} {:>12} {:>12} {:>12} {:>10} {:>10}",
+        "algorithm", "input", "n", "comparisons", "swaps", "writes", "/ n log n", "/ n^2"
+    )
+    .unwrap();
+    for sorter in sorters {
+        for (name, input) in inputs {
+            let mut arr = input.clone();
+            let mut stats = SortStats::default();
+            sorter.sort_by_observed(&mut arr, &mut T::cmp, &mut stats);
+            let n = arr.len().max(2) as f64;
+            writeln!(
+                table,
+                "{:<18} {:<10} {:>7} {:>12} {:>12} {:>12} {:>10.2} {:>10.4}",
+                sorter.name(),
+                name,
+                arr.len(),
+                stats.comparisons,
+                stats.swaps,
+                stats.writes,
+                stats.comparisons as f64 / (n * n.log2()),
+                stats.comparisons as f64 / (n * n),
+            )
+            .unwrap();
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::sorter::sorters;
+
+    #[test]
+    fn test_stats_match_comparator_calls() {
+        let input: Vec<u32> = (0..300).map(|i| i * 7919 % 301 / 2).collect();
+        for sorter in sorters::<u32>() {
+            let mut arr = input.clone();
+            let mut stats = SortStats::default();
+            let mut calls = 0;
+            sorter.sort_by_observed(
+                &mut arr,
+                &mut |a, b| {
+                    calls += 1;
+                    a.cmp(b)
+                },
+                &mut stats,
+            );
+            assert!(arr.windows(2).all(|pair| pair[0] <= pair[1]), "{}", sorter.name());
+            assert_eq!(stats.comparisons, calls, "{}", sorter.name());
+            assert!(stats.writes >= 2 * stats.swaps, "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_stats_table() {
+        let mut inputs = Vec::new();
+        for n in [100, 1000] {
+            inputs.push(("random", (0..n).map(|i: u64| i.wrapping_mul(2654435761) % 1009).collect::<Vec<_>>()));
+            inputs.push(("sorted", (0..n).collect()));
+            inputs.push(("reversed", (0..n).rev().collect()));
+        }
+        let table = stats_table(&sorters(), &inputs);
+        assert_eq!(table.lines().count(), 1 + sorters::<u64>().len() * inputs.len());
+
+        let row = |algorithm: &str, input: &str, n: &str| {
+            table
+                .lines()
+                .find(|line| line.split_whitespace().take(3).eq([algorithm, input, n]))
+                .unwrap()
+                .split_whitespace()
+                .map(|column| column.to_string())
+                .collect::<Vec<_>>()
+        };
+        // Bubble sort stops after a single pass over sorted input
+        assert_eq!(row("bubble", "sorted", "1000")[3], "999");
+        assert_eq!(row("bubble", "sorted", "1000")[4], "0");
+        // Merge sort stays below n log n comparisons, cycle sort needs fewer swaps than elements
+        assert!(row("merge", "random", "1000")[3].parse::<f64>().unwrap() < 1000.0 * 1000f64.log2());
+        assert!(row("cycle", "random", "1000")[4].parse::<u64>().unwrap() < 1000);
+    }
+}
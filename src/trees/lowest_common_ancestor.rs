@@ -0,0 +1,393 @@
+/**
+ * Lowest Common Ancestor
+ *
+ * The lowest common ancestor (LCA) of two nodes in a rooted tree is the deepest node that has both of
+ * them in its subtree. With it, the distance between two nodes is
+ * `depth(a) + depth(b) - 2 * depth(lca(a, b))` and the path between them goes up from `a` to the LCA
+ * and back down to `b`.
+ *
+ * `RootedTree` is built from the `predecessors` maps returned by `bfs_graph::bfs_tree` and
+ * `dfs_graph::dfs_tree` (or any other child -> parent map). Two structures answer LCA queries on it:
+ *
+ * - `BinaryLifting` stores the 2^k-th ancestor of every node. A query first lifts the deeper node to
+ *   the depth of the other, then lifts both while their ancestors differ.
+ *   Build: O(V log V), Query: O(log V), Space: O(V log V)
+ * - `EulerTourLca` records the nodes of a depth first walk every time it enters or returns to them.
+ *   Between the first visits of `a` and `b` the walk passes through their LCA and no shallower node,
+ *   so a query is a range minimum over depths, answered by a sparse table.
+ *   Build: O(V log V), Query: O(1), Space: O(V log V)
+ */
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub struct RootedTree<N> {
+    ids: Vec<N>,
+    index: HashMap<N, usize>,
+    parent: Vec<usize>,
+    children: Vec<Vec<usize>>,
+    depth: Vec<usize>,
+}
+
+impl<N: Hash + Eq + Clone> RootedTree<N> {
+    /**
+     * Build a tree from a map from every node to its parent, as returned by `bfs_tree` and `dfs_tree`.
+     * The entry of the root itself (which maps to '\0' in those maps) is ignored.
+     *
+     * Time Complexity: O(V)
+     *
+     * @param predecessors The parent of every node except the root
+     * @param root The root of the tree
+     * @return The tree, `None` if some node does not lead back to the root
+     */
+    pub fn from_predecessors(predecessors: &HashMap<N, N>, root: &N) -> Option<Self> {
+        let mut ids = vec![root.clone()];
+        let mut index = HashMap::from([(root.clone(), 0)]);
+        let mut edges = Vec::new();
+        for (child, parent) in predecessors {
+            if child == root {
+                continue;
+            }
+            for node in [child, parent] {
+                if !index.contains_key(node) {
+                    index.insert(node.clone(), ids.len());
+                    ids.push(node.clone());
+                }
+            }
+            edges.push((index[child], index[parent]));
+        }
+
+        let n = ids.len();
+        let mut parent = vec![0; n];
+        let mut children = vec![Vec::new(); n];
+        for (child, of) in edges {
+            parent[child] = of;
+            children[of].push(child);
+        }
+
+        // Nodes on a cycle or under a parent without a parent of its own are never reached
+        let mut depth = vec![usize::MAX; n];
+        depth[0] = 0;
+        let mut stack = vec![0];
+        let mut reached = 1;
+        while let Some(node) = stack.pop() {
+            for &child in &children[node] {
+                depth[child] = depth[node] + 1;
+                reached += 1;
+                stack.push(child);
+            }
+        }
+        if reached != n {
+            return None;
+        }
+
+        Some(RootedTree { ids, index, parent, children, depth })
+    }
+
+    pub fn root(&self) -> &N {
+        &self.ids[0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    /**
+     * The parent of `node`, `None` for the root and unknown nodes.
+     */
+    pub fn parent(&self, node: &N) -> Option<&N> {
+        let node = *self.index.get(node)?;
+        (node != 0).then(|| &self.ids[self.parent[node]])
+    }
+
+    /**
+     * The number of edges between `node` and the root.
+     */
+    pub fn depth(&self, node: &N) -> Option<usize> {
+        self.index.get(node).map(|&node| self.depth[node])
+    }
+
+    // The nodes on the path from `a` up to `ancestor` and down to `b`.
+    fn path_through(&self, a: usize, b: usize, ancestor: usize) -> Vec<N> {
+        let mut up = vec![a];
+        while *up.last().unwrap() != ancestor {
+            up.push(self.parent[*up.last().unwrap()]);
+        }
+        let mut down = Vec::new();
+        let mut node = b;
+        while node != ancestor {
+            down.push(node);
+            node = self.parent[node];
+        }
+        up.into_iter().chain(down.into_iter().rev()).map(|node| self.ids[node].clone()).collect()
+    }
+}
+
+/**
+ * Queries answered by both LCA structures.
+ */
+pub trait LowestCommonAncestor<N: Hash + Eq + Clone> {
+    fn tree(&self) -> &RootedTree<N>;
+
+    // The LCA of two node indices of `tree()`.
+    fn lca_index(&self, a: usize, b: usize) -> usize;
+
+    /**
+     * The lowest common ancestor of `a` and `b`, `None` if either is not in the tree.
+     */
+    fn lca(&self, a: &N, b: &N) -> Option<&N> {
+        let tree = self.tree();
+        let (a, b) = (*tree.index.get(a)?, *tree.index.get(b)?);
+        Some(&tree.ids[self.lca_index(a, b)])
+    }
+
+    /**
+     * The number of edges on the path between `a` and `b`.
+     */
+    fn distance(&self, a: &N, b: &N) -> Option<usize> {
+        let tree = self.tree();
+        let (a, b) = (*tree.index.get(a)?, *tree.index.get(b)?);
+        Some(tree.depth[a] + tree.depth[b] - 2 * tree.depth[self.lca_index(a, b)])
+    }
+
+    /**
+     * The nodes on the path from `a` to `b`, both included.
+     *
+     * Time Complexity: O(query + path length)
+     */
+    fn path(&self, a: &N, b: &N) -> Option<Vec<N>> {
+        let tree = self.tree();
+        let (a, b) = (*tree.index.get(a)?, *tree.index.get(b)?);
+        Some(tree.path_through(a, b, self.lca_index(a, b)))
+    }
+}
+
+/**
+ * Binary Lifting
+ *
+ * `ancestors[k][v]` is the ancestor 2^k levels above `v`, or the root if the tree is not that deep.
+ */
+pub struct BinaryLifting<N> {
+    tree: RootedTree<N>,
+    ancestors: Vec<Vec<usize>>,
+}
+
+impl<N: Hash + Eq + Clone> BinaryLifting<N> {
+    pub fn new(tree: RootedTree<N>) -> Self {
+        let n = tree.len();
+        let levels = (usize::BITS - n.leading_zeros()).max(1) as usize;
+        let mut ancestors = vec![tree.parent.clone()];
+        for k in 1..levels {
+            let previous = &ancestors[k - 1];
+            let next = (0..n).map(|node| previous[previous[node]]).collect();
+            ancestors.push(next);
+        }
+        BinaryLifting { tree, ancestors }
+    }
+
+    /**
+     * The ancestor `k` levels above `node`, `None` if `node` is less than `k` levels deep.
+     *
+     * Time Complexity: O(log V)
+     */
+    pub fn kth_ancestor(&self, node: &N, k: usize) -> Option<&N> {
+        let mut node = *self.tree.index.get(node)?;
+        if k > self.tree.depth[node] {
+            return None;
+        }
+        for (level, ancestors) in self.ancestors.iter().enumerate() {
+            if k & (1 << level) != 0 {
+                node = ancestors[node];
+            }
+        }
+        Some(&self.tree.ids[node])
+    }
+}
+
+impl<N: Hash + Eq + Clone> LowestCommonAncestor<N> for BinaryLifting<N> {
+    fn tree(&self) -> &RootedTree<N> {
+        &self.tree
+    }
+
+    fn lca_index(&self, mut a: usize, mut b: usize) -> usize {
+        let depth = &self.tree.depth;
+        if depth[a] < depth[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        let lift = depth[a] - depth[b];
+        for (level, ancestors) in self.ancestors.iter().enumerate() {
+            if lift & (1 << level) != 0 {
+                a = ancestors[a];
+            }
+        }
+        if a == b {
+            return a;
+        }
+        for ancestors in self.ancestors.iter().rev() {
+            if ancestors[a] != ancestors[b] {
+                a = ancestors[a];
+                b = ancestors[b];
+            }
+        }
+        self.tree.parent[a]
+    }
+}
+
+/**
+ * Euler Tour + Sparse Table
+ *
+ * `sparse[k][i]` is the shallowest node among `tour[i..i + 2^k]`, so any range is covered by two
+ * overlapping power of two ranges.
+ */
+pub struct EulerTourLca<N> {
+    tree: RootedTree<N>,
+    first: Vec<usize>,
+    sparse: Vec<Vec<usize>>,
+}
+
+impl<N: Hash + Eq + Clone> EulerTourLca<N> {
+    pub fn new(tree: RootedTree<N>) -> Self {
+        let n = tree.len();
+        let mut tour = Vec::with_capacity(2 * n - 1);
+        let mut first = vec![0; n];
+        let mut stack = vec![(0, 0)];
+        while let Some(frame) = stack.last_mut() {
+            let (node, child) = *frame;
+            if child == 0 {
+                first[node] = tour.len();
+            }
+            tour.push(node);
+            if let Some(&next) = tree.children[node].get(child) {
+                frame.1 += 1;
+                stack.push((next, 0));
+            } else {
+                stack.pop();
+            }
+        }
+
+        let shallower = |a: usize, b: usize| if tree.depth[a] <= tree.depth[b] { a } else { b };
+        let mut sparse = vec![tour];
+        let mut width = 1;
+        while 2 * width <= sparse[0].len() {
+            let previous = sparse.last().unwrap();
+            let next = (0..previous.len() - width)
+                .map(|i| shallower(previous[i], previous[i + width]))
+                .collect();
+            sparse.push(next);
+            width *= 2;
+        }
+
+        EulerTourLca { tree, first, sparse }
+    }
+}
+
+impl<N: Hash + Eq + Clone> LowestCommonAncestor<N> for EulerTourLca<N> {
+    fn tree(&self) -> &RootedTree<N> {
+        &self.tree
+    }
+
+    fn lca_index(&self, a: usize, b: usize) -> usize {
+        let (mut start, mut end) = (self.first[a], self.first[b]);
+        if start > end {
+            std::mem::swap(&mut start, &mut end);
+        }
+        let level = (usize::BITS - 1 - (end - start + 1).leading_zeros()) as usize;
+        let (left, right) = (self.sparse[level][start], self.sparse[level][end + 1 - (1 << level)]);
+        if self.tree.depth[left] <= self.tree.depth[right] { left } else { right }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::bfs_graph::bfs_tree;
+    use crate::graphs::dfs_graph::dfs_tree;
+    use crate::graphs::generators::{self, to_adjacency_lists, GeneratorConfig};
+
+    fn create_test_graph() -> HashMap<char, Vec<char>> {
+        let mut graph = HashMap::new();
+        graph.insert('A', vec!['B', 'C']);
+        graph.insert('B', vec!['D', 'E']);
+        graph.insert('C', vec!['F']);
+        graph.insert('E', vec!['G', 'H']);
+        graph.insert('F', vec!['I']);
+        graph
+    }
+
+    #[test]
+    fn test_lowest_common_ancestor() {
+        let tree = RootedTree::from_predecessors(&bfs_tree(&create_test_graph(), 'A'), &'A').unwrap();
+        assert_eq!(tree.len(), 9);
+        assert_eq!(tree.depth(&'H'), Some(3));
+        assert_eq!(tree.parent(&'H'), Some(&'E'));
+        assert_eq!(tree.parent(&'A'), None);
+
+        let lifting = BinaryLifting::new(tree);
+        assert_eq!(lifting.kth_ancestor(&'H', 2), Some(&'B'));
+        assert_eq!(lifting.kth_ancestor(&'H', 4), None);
+        let tree = RootedTree::from_predecessors(&dfs_tree(&create_test_graph(), 'A'), &'A').unwrap();
+        let euler = EulerTourLca::new(tree);
+
+        let queries: [&dyn LowestCommonAncestor<char>; 2] = [&lifting, &euler];
+        for lca in queries {
+            assert_eq!(lca.lca(&'G', &'H'), Some(&'E'));
+            assert_eq!(lca.lca(&'D', &'H'), Some(&'B'));
+            assert_eq!(lca.lca(&'G', &'I'), Some(&'A'));
+            assert_eq!(lca.lca(&'E', &'G'), Some(&'E'));
+            assert_eq!(lca.lca(&'F', &'F'), Some(&'F'));
+            assert_eq!(lca.lca(&'F', &'Z'), None);
+            assert_eq!(lca.distance(&'G', &'I'), Some(6));
+            assert_eq!(lca.path(&'D', &'G'), Some(vec!['D', 'B', 'E', 'G']));
+            assert_eq!(lca.path(&'C', &'I'), Some(vec!['C', 'F', 'I']));
+        }
+    }
+
+    #[test]
+    fn test_invalid_predecessors() {
+        // C and D point at each other and never reach the root
+        let predecessors = HashMap::from([('A', '\0'), ('B', 'A'), ('C', 'D'), ('D', 'C')]);
+        assert!(RootedTree::from_predecessors(&predecessors, &'A').is_none());
+        let single = RootedTree::from_predecessors(&HashMap::new(), &'A').unwrap();
+        assert_eq!(EulerTourLca::new(single).lca(&'A', &'A'), Some(&'A'));
+    }
+
+    #[test]
+    fn test_matches_naive_ancestors() {
+        let graph = generators::tree(300, &GeneratorConfig { seed: 9, ..GeneratorConfig::default() });
+        let label = |node: u32| char::from_u32(node + 0x100).unwrap();
+        let graph: HashMap<char, Vec<char>> = to_adjacency_lists(&graph)
+            .into_iter()
+            .map(|(node, neighbors)| (label(node), neighbors.into_iter().map(label).collect()))
+            .collect();
+        let root = label(0);
+        let predecessors = bfs_tree(&graph, root);
+        let lifting = BinaryLifting::new(RootedTree::from_predecessors(&predecessors, &root).unwrap());
+        let euler = EulerTourLca::new(RootedTree::from_predecessors(&predecessors, &root).unwrap());
+
+        let ancestors = |mut node: char| {
+            let mut chain = vec![node];
+            while node != root {
+                node = predecessors[&node];
+                chain.push(node);
+            }
+            chain
+        };
+        let nodes: Vec<char> = graph.keys().copied().collect();
+        for &a in nodes.iter().step_by(7) {
+            for &b in nodes.iter().step_by(5) {
+                let of_b = ancestors(b);
+                let expected = ancestors(a).into_iter().find(|node| of_b.contains(node)).unwrap();
+                assert_eq!(lifting.lca(&a, &b), Some(&expected));
+                assert_eq!(euler.lca(&a, &b), Some(&expected));
+                assert_eq!(lifting.distance(&a, &b), euler.distance(&a, &b));
+            }
+        }
+    }
+}
@@ -1 +1,2 @@
-pub mod trees;
\ No newline at end of file
+pub mod trees;
+pub mod lowest_common_ancestor;
\ No newline at end of file